@@ -1,39 +1,285 @@
-use reqwest;
-use serde_json;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Serialize;
 use std::collections::BTreeMap;
+use std::sync::{PoisonError, RwLock};
+use std::time::Duration;
 
 // TODO: fix these to specifics
 use super::data::*;
 use super::error;
 use super::queries::*;
+use super::utils::check_status;
 
 #[derive(Debug)]
 /// Main handle and access point to working with qbittorrent
 ///
 /// Full documentation on provided methods is available here [here](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-4.1))
+///
+/// When the credentials are kept (the default for `Api::new`), a request that is rejected
+/// because the session expired logs in again and is retried once.
 pub struct Api {
-    pub(crate) cookie: String,
+    pub(crate) cookie: RwLock<String>,
     pub(crate) address: String,
     pub(crate) client: reqwest::Client,
+    pub(crate) credentials: Option<Credentials>,
+    /// Extra headers sent with every request
+    pub(crate) headers: HeaderMap,
+    pub(crate) timeout: Option<Duration>,
+}
+
+/// Login information kept around to renew an expired session
+#[derive(Clone)]
+pub(crate) struct Credentials {
+    pub(crate) username: String,
+    pub(crate) password: String,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"<hidden>")
+            .finish()
+    }
+}
+
+/// Configure how an `Api` connects to qbittorrent
+///
+/// ```ignore
+/// let api = Api::builder("https://example.com/qbt/")
+///     .credentials("admin", "adminadmin")
+///     .timeout(Duration::from_secs(10))
+///     .add_root_certificate(reqwest::Certificate::from_pem(&internal_ca)?)
+///     .build()
+///     .await?;
+/// ```
+#[derive(Debug)]
+pub struct ApiBuilder {
+    address: String,
+    credentials: Option<Credentials>,
+    reauthenticate: bool,
+    client: Option<reqwest::Client>,
+    timeout: Option<Duration>,
+    root_certificates: Vec<reqwest::Certificate>,
+    accept_invalid_certs: bool,
+    headers: HeaderMap,
+}
+
+impl ApiBuilder {
+    /// `address` is the root of the WebUI, including the sub-path when it is served behind
+    /// a reverse proxy (`https://example.com/qbt/`)
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.trim_end_matches('/').to_string(),
+            credentials: None,
+            reauthenticate: true,
+            client: None,
+            timeout: None,
+            root_certificates: Vec::new(),
+            accept_invalid_certs: false,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    pub fn credentials(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some(Credentials {
+            username: username.to_string(),
+            password: password.to_string(),
+        });
+        self
+    }
+
+    /// Keep the credentials to log in again when the session expires (default `true`)
+    pub fn reauthenticate(mut self, reauthenticate: bool) -> Self {
+        self.reauthenticate = reauthenticate;
+        self
+    }
+
+    /// Use a preconfigured client instead of building one
+    ///
+    /// The TLS options of this builder only apply to the client it builds itself and are
+    /// ignored when a client is given here.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Timeout of each request, from connecting until the response body is read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Trust an additional root certificate, e.g. an internal CA
+    pub fn add_root_certificate(mut self, certificate: reqwest::Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Accept any certificate, including self-signed and expired ones
+    ///
+    /// This makes the connection vulnerable to man-in-the-middle attacks, prefer
+    /// `add_root_certificate` where possible.
+    pub fn danger_accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    /// Headers sent with every request, e.g. authentication for a reverse proxy
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Build the client and log in
+    ///
+    /// Without credentials no login is attempted, which only works against a server that
+    /// bypasses authentication for this client.
+    pub async fn build(self) -> Result<Api, error::Error> {
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder()
+                    .danger_accept_invalid_certs(self.accept_invalid_certs);
+                for certificate in self.root_certificates {
+                    builder = builder.add_root_certificate(certificate);
+                }
+                builder.build()?
+            }
+        };
+
+        let mut api = Api {
+            cookie: RwLock::new(String::new()),
+            address: self.address,
+            client,
+            credentials: self.credentials,
+            headers: self.headers,
+            timeout: self.timeout,
+        };
+
+        if api.credentials.is_some() {
+            api.reauthenticate().await?;
+        } else {
+            // authentication is bypassed for this host (localhost or a whitelisted subnet),
+            // make sure that the server agrees
+            let addr = push_own! {api.address, "/api/v2/app/version"};
+            let res = api.prepare(api.client.get(&addr))?.send().await?;
+
+            if res.status() == StatusCode::FORBIDDEN {
+                return Err(error::Error::Forbidden);
+            }
+            res.error_for_status()?;
+        }
+
+        if !self.reauthenticate {
+            api.forget_credentials();
+        }
+
+        Ok(api)
+    }
 }
 
 impl Api {
     pub async fn new(username: &str, password: &str, address: &str) -> Result<Self, error::Error> {
-        let client = reqwest::Client::new();
+        Self::builder(address)
+            .credentials(username, password)
+            .build()
+            .await
+    }
+
+    /// Connect to a server that does not require authentication for this client, because it
+    /// is on localhost or in a whitelisted subnet
+    pub async fn new_unauthenticated(address: &str) -> Result<Self, error::Error> {
+        Self::builder(address).build().await
+    }
+
+    /// Configure the connection in more detail than `Api::new` allows
+    pub fn builder(address: &str) -> ApiBuilder {
+        ApiBuilder::new(address)
+    }
 
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert("Referer", address.parse()?);
+    /// Forget the username and password. An expired session will no longer be renewed and
+    /// requests will fail with `Error::Forbidden` instead
+    pub fn forget_credentials(&mut self) {
+        self.credentials = None;
+    }
+
+    /// End the session on the server
+    ///
+    /// A session that already expired is not an error.
+    pub async fn logout(self) -> Result<(), error::Error> {
+        let addr = push_own! {self.address, "/api/v2/auth/logout"};
+
+        let res = self.prepare(self.client.post(&addr))?.send().await?;
 
-        let addr = push_own! {address, "/api/v2/auth/login", "?username=", username, "&password=", password};
-        let response = client.get(&addr).headers(headers).send().await?;
+        if res.status() == StatusCode::FORBIDDEN {
+            return Ok(());
+        }
 
-        let headers = match response.headers().get("set-cookie") {
+        match res.error_for_status() {
+            Ok(_) => Ok(()),
+            Err(e) => Err(error::Error::from(e)),
+        }
+    }
+
+    /// Check if the current session cookie is still accepted by the server
+    ///
+    /// Unlike other requests, this never logs in again.
+    pub async fn is_session_valid(&self) -> Result<bool, error::Error> {
+        let addr = push_own! {self.address, "/api/v2/app/version"};
+
+        let res = self.prepare(self.client.get(&addr))?.send().await?;
+
+        if res.status() == StatusCode::FORBIDDEN {
+            return Ok(false);
+        }
+
+        match res.error_for_status() {
+            Ok(_) => Ok(true),
+            Err(e) => Err(error::Error::from(e)),
+        }
+    }
+
+    /// Log in and return the session cookie
+    async fn login(&self, credentials: &Credentials) -> Result<String, error::Error> {
+        let addr = push_own! {self.address, "/api/v2/auth/login"};
+        let form = [
+            ("username", credentials.username.as_str()),
+            ("password", credentials.password.as_str()),
+        ];
+
+        let mut request = self
+            .client
+            .post(&addr)
+            .headers(self.headers.clone())
+            .header("Referer", self.address.as_str())
+            .form(&form);
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+
+        let response = request.send().await?;
+
+        // qbittorrent answers 403 once the ip is banned for too many failed attempts
+        if response.status() == StatusCode::FORBIDDEN {
+            return Err(error::Error::Banned);
+        }
+
+        let set_cookie = response.headers().get("set-cookie").cloned();
+
+        // wrong credentials are still a 200, with "Fails." as the body
+        if response.text().await?.trim() == "Fails." {
+            return Err(error::Error::LoginFailed);
+        }
+
+        let headers = match &set_cookie {
             Some(header) => header,
             None => return Err(error::Error::MissingHeaders),
         };
 
         let cookie_str = headers.to_str()?;
-        let cookie_header = match cookie_str.find(";") {
+        let cookie_header = match cookie_str.find(';') {
             Some(index) => index,
             None => return Err(error::Error::MissingCookie),
         };
@@ -44,24 +290,83 @@ impl Api {
             None => return Err(error::Error::SliceError),
         };
 
-        Ok(Self {
-            cookie: cookie.to_string(),
-            address: address.to_string(),
-            client,
-        })
+        Ok(cookie.to_string())
+    }
+
+    /// Replace the session cookie with a fresh one
+    async fn reauthenticate(&self) -> Result<(), error::Error> {
+        let credentials = match &self.credentials {
+            Some(credentials) => credentials,
+            None => return Err(error::Error::Forbidden),
+        };
+
+        let cookie = self.login(credentials).await?;
+        *self.cookie.write().unwrap_or_else(PoisonError::into_inner) = cookie;
+
+        Ok(())
+    }
+
+    /// Add the session cookie, default headers and timeout to a request
+    fn prepare(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::RequestBuilder, error::Error> {
+        let request = request.headers(self.make_headers()?);
+
+        match self.timeout {
+            Some(timeout) => Ok(request.timeout(timeout)),
+            None => Ok(request),
+        }
+    }
+
+    /// Send an authenticated request built by `request`
+    ///
+    /// If qbittorrent rejects the session (403), log in again and send the request a second
+    /// time. `request` is called once per attempt since a `RequestBuilder` can not always be
+    /// cloned.
+    ///
+    /// qbittorrent also answers 403 when the session is fine but the request is not allowed,
+    /// e.g. a directory that can not be written to. That is `Error::AccessDenied` and is not
+    /// retried.
+    pub(crate) async fn send<F>(&self, request: F) -> Result<reqwest::Response, error::Error>
+    where
+        F: Fn(&reqwest::Client) -> reqwest::RequestBuilder + Send + Sync,
+    {
+        let res = self.prepare(request(&self.client))?.send().await?;
+
+        if res.status() != StatusCode::FORBIDDEN {
+            return Ok(res);
+        }
+
+        // a rejected session comes without a reason, a denied request explains itself
+        let body = res.text().await?;
+        if !body.is_empty() && body != "Forbidden" {
+            return Err(error::Error::AccessDenied(body));
+        }
+
+        self.reauthenticate().await?;
+
+        let res = self.prepare(request(&self.client))?.send().await?;
+
+        // the session was just renewed, so this is not about the session
+        if res.status() == StatusCode::FORBIDDEN {
+            Err(error::Error::AccessDenied(res.text().await?))
+        } else {
+            Ok(res)
+        }
     }
 
     pub async fn application_version(&self) -> Result<String, error::Error> {
         let addr = push_own! {self.address, "/api/v2/app/version"};
 
-        let res = self.client.get(&addr).send().await?.text().await?;
+        let res = self.send(|client| client.get(&addr)).await?.text().await?;
         Ok(res)
     }
 
     pub async fn api_version(&self) -> Result<String, error::Error> {
         let addr = push_own! {self.address, "/api/v2/app/webapiVersion"};
 
-        let res = self.client.get(&addr).send().await?.text().await?;
+        let res = self.send(|client| client.get(&addr)).await?.text().await?;
         Ok(res)
     }
 
@@ -69,7 +374,7 @@ impl Api {
     pub async fn build_info(&self) -> Result<BuildInfo, error::Error> {
         let addr = push_own! {self.address, "/api/v2/app/buildInfo"};
 
-        let res = self.client.get(&addr).send().await?.bytes().await?;
+        let res = self.send(|client| client.get(&addr)).await?.bytes().await?;
 
         let info = serde_json::from_slice(&res)?;
 
@@ -79,15 +384,45 @@ impl Api {
     pub async fn shutdown(&self) -> Result<(), error::Error> {
         let addr = push_own! {self.address, "/api/v2/app/shutdown"};
 
-        self.client.get(&addr).send().await?;
+        self.send(|client| client.get(&addr)).await?;
 
         Ok(())
     }
 
+    pub async fn get_preferences(&self) -> Result<Preferences, error::Error> {
+        let addr = push_own! {self.address, "/api/v2/app/preferences"};
+
+        let res = self.send(|client| client.get(&addr)).await?.bytes().await?;
+
+        let prefs = serde_json::from_slice(&res)?;
+        Ok(prefs)
+    }
+
+    /// Change the preferences that are set (`Some`) in `prefs`, leaving all others untouched
+    pub async fn set_preferences(&self, prefs: &Preferences) -> Result<(), error::Error> {
+        let addr = push_own! {self.address, "/api/v2/app/setPreferences"};
+
+        // qbittorrent 4.6 (WebUI API 2.9.2) replaced the proxy type codes with names
+        let legacy_proxy = match prefs.proxy_type() {
+            Some(_) => api_older_than(&self.api_version().await?, &[2, 9, 2]),
+            None => false,
+        };
+        let json = serde_json::to_string(&prefs.to_json(legacy_proxy)?)?;
+
+        let res = self
+            .send(|client| client.post(&addr).form(&[("json", &json)]))
+            .await?;
+
+        match res.error_for_status() {
+            Ok(_) => Ok(()),
+            Err(e) => Err(error::Error::from(e)),
+        }
+    }
+
     pub async fn default_save_path(&self) -> Result<String, error::Error> {
         let addr = push_own! {self.address, "/api/v2/app/defaultSavePath"};
 
-        let res = self.client.get(&addr).send().await?;
+        let res = self.send(|client| client.get(&addr)).await?;
 
         Ok(res.text().await?)
     }
@@ -101,7 +436,7 @@ impl Api {
         let url = format! {"/api/v2/log/main?{}", log_request.url()};
         let addr = push_own! {self.address, &url};
 
-        let res = self.client.get(&addr).send().await?.bytes().await?;
+        let res = self.send(|client| client.get(&addr)).await?.bytes().await?;
 
         let log: Vec<Log> = serde_json::from_slice(&res)?;
 
@@ -113,6 +448,7 @@ impl Api {
     // ##### Sync
     // #####
 
+    // sync is implemented in sync.rs
     // get_torrent_peers is a trait
 
     // #####
@@ -124,14 +460,7 @@ impl Api {
     pub async fn get_global_transfer_info(&self) -> Result<GlobalTransferInfo, error::Error> {
         let addr = push_own! {self.address, "/api/v2/transfer/info"};
 
-        let res = self
-            .client
-            .get(&addr)
-            .headers(self.make_headers()?)
-            .send()
-            .await?
-            .bytes()
-            .await?;
+        let res = self.send(|client| client.get(&addr)).await?.bytes().await?;
 
         let x = serde_json::from_slice(&res)?;
         Ok(x)
@@ -139,14 +468,7 @@ impl Api {
 
     pub async fn get_alternate_speed_limits_state(&self) -> Result<AlternateLimits, error::Error> {
         let addr = push_own! {self.address, "/api/v2/transfer/speedLimitsMode"};
-        let res = self
-            .client
-            .get(&addr)
-            .headers(self.make_headers()?)
-            .send()
-            .await?
-            .bytes()
-            .await?;
+        let res = self.send(|client| client.get(&addr)).await?.bytes().await?;
 
         if res.as_ref() == b"1" {
             Ok(AlternateLimits::Enabled)
@@ -160,7 +482,7 @@ impl Api {
     pub async fn toggle_alternative_speed_limits(&self) -> Result<(), error::Error> {
         let addr = push_own! {self.address, "/api/v2/transfer/toggleSpeedLimitsMode"};
 
-        let res = self.client.get(&addr).send().await?;
+        let res = self.send(|client| client.get(&addr)).await?;
 
         match res.error_for_status() {
             Ok(_) => Ok(()),
@@ -168,20 +490,58 @@ impl Api {
         }
     }
 
+    /// Global download limit, without alternative speed limits
+    pub async fn get_global_download_limit(&self) -> Result<SpeedLimit, error::Error> {
+        self.get_global_limit("/api/v2/transfer/downloadLimit")
+            .await
+    }
+
+    pub async fn set_global_download_limit(&self, limit: SpeedLimit) -> Result<(), error::Error> {
+        self.set_global_limit("/api/v2/transfer/setDownloadLimit", limit)
+            .await
+    }
+
+    /// Global upload limit, without alternative speed limits
+    pub async fn get_global_upload_limit(&self) -> Result<SpeedLimit, error::Error> {
+        self.get_global_limit("/api/v2/transfer/uploadLimit").await
+    }
+
+    pub async fn set_global_upload_limit(&self, limit: SpeedLimit) -> Result<(), error::Error> {
+        self.set_global_limit("/api/v2/transfer/setUploadLimit", limit)
+            .await
+    }
+
+    async fn get_global_limit(&self, endpoint: &str) -> Result<SpeedLimit, error::Error> {
+        let addr = push_own! {self.address, endpoint};
+
+        let res = self.send(|client| client.get(&addr)).await?;
+        let res = check_status(res).await?.bytes().await?;
+
+        // a bare integer of bytes/s
+        let limit = serde_json::from_slice(&res)?;
+        Ok(limit)
+    }
+
+    async fn set_global_limit(
+        &self,
+        endpoint: &str,
+        limit: SpeedLimit,
+    ) -> Result<(), error::Error> {
+        let addr = push_own! {self.address, endpoint};
+        let form = [("limit", i64::from(limit))];
+
+        let res = self.send(|client| client.post(&addr).form(&form)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+
     // ban_peers is a trait
 
     // TODO: extra filtering parameters here
     pub async fn get_torrent_list(&self) -> Result<Vec<Torrent>, error::Error> {
         let addr = push_own! {self.address, "/api/v2/torrents/info"};
 
-        let res = self
-            .client
-            .get(&addr)
-            .headers(self.make_headers()?)
-            .send()
-            .await?
-            .bytes()
-            .await?;
+        let res = self.send(|client| client.get(&addr)).await?.bytes().await?;
 
         let all_torrents: Vec<Torrent> = serde_json::from_slice(&res)?;
 
@@ -191,28 +551,80 @@ impl Api {
     pub async fn add_new_torrent(&self, data: &TorrentDownload) -> Result<(), error::Error> {
         let addr = push_own! {self.address, "/api/v2/torrents/add"};
 
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert("cookie", self.cookie.parse()?);
-        headers.insert("Referer", self.address.parse()?);
+        if data.torrent_files().is_empty() {
+            let res = self
+                .send(|client| {
+                    client
+                        .post(&addr)
+                        .form(data)
+                        .header("Referer", self.address.as_str())
+                })
+                .await?;
+
+            return check_added(res).await;
+        }
+
+        // .torrent files have to be sent as multipart/form-data, along with every other
+        // field as a text part
+        let fields = match serde_json::to_value(data)? {
+            serde_json::Value::Object(fields) => fields,
+            _ => unreachable!("TorrentDownload serializes to a map"),
+        };
+        let files = data
+            .torrent_files()
+            .iter()
+            .map(|x| x.load())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // a multipart form can only be sent once, so it is rebuilt if the request is retried
+        let multipart = || {
+            let mut form = reqwest::multipart::Form::new();
+
+            for (key, value) in &fields {
+                let text = match value {
+                    serde_json::Value::Null => continue,
+                    serde_json::Value::String(x) => x.clone(),
+                    other => other.to_string(),
+                };
+                form = form.text(key.clone(), text);
+            }
+
+            for (name, bytes) in &files {
+                let part = reqwest::multipart::Part::bytes(bytes.clone())
+                    .file_name(name.clone())
+                    .mime_str("application/x-bittorrent")
+                    .expect("the mime type is valid");
+                form = form.part("torrents", part);
+            }
+
+            form
+        };
 
         let res = self
-            .client
-            .post(&addr)
-            .form(data)
-            .headers(headers)
-            .send()
+            .send(|client| {
+                client
+                    .post(&addr)
+                    .multipart(multipart())
+                    .header("Referer", self.address.as_str())
+            })
             .await?;
 
-        match res.error_for_status() {
-            Ok(_) => Ok(()),
-            Err(e) => Err(error::Error::from(e)),
-        }
+        check_added(res).await
     }
 
     /// Make the authentication headers for each request
     pub(crate) fn make_headers(&self) -> Result<reqwest::header::HeaderMap, error::Error> {
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert("cookie", self.cookie.parse()?);
+        let cookie = self
+            .cookie
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+
+        let mut headers = self.headers.clone();
+        // there is no session when authentication is bypassed
+        if !cookie.is_empty() {
+            headers.insert("cookie", cookie.parse()?);
+        }
         Ok(headers)
     }
 
@@ -220,40 +632,170 @@ impl Api {
     pub async fn get_all_categories(&self) -> Result<BTreeMap<String, Categories>, error::Error> {
         let addr = push_own!(self.address, "/api/v2/torrents/categories");
 
-        let res = self
-            .client
-            .get(&addr)
-            .headers(self.make_headers()?)
-            .send()
-            .await?
-            .bytes()
-            .await?;
+        let res = self.send(|client| client.get(&addr)).await?.bytes().await?;
 
         let x = serde_json::from_slice(&res)?;
         Ok(x)
     }
 
+    /// list all tags that currently exist
+    pub async fn get_all_tags(&self) -> Result<Vec<String>, error::Error> {
+        let addr = push_own!(self.address, "/api/v2/torrents/tags");
+
+        let res = self.send(|client| client.get(&addr)).await?;
+        let res = check_status(res).await?.bytes().await?;
+
+        let tags = serde_json::from_slice(&res)?;
+        Ok(tags)
+    }
+
+    /// Create tags without adding them to any torrent. Existing tags are left as they are
+    pub async fn create_tags(&self, tags: &[String]) -> Result<(), error::Error> {
+        self.edit_tags("/api/v2/torrents/createTags", tags).await
+    }
+
+    /// Delete tags, removing them from every torrent
+    pub async fn delete_tags(&self, tags: &[String]) -> Result<(), error::Error> {
+        self.edit_tags("/api/v2/torrents/deleteTags", tags).await
+    }
+
+    async fn edit_tags(&self, endpoint: &str, tags: &[String]) -> Result<(), error::Error> {
+        if tags.is_empty() {
+            return Ok(());
+        }
+
+        let addr = push_own!(self.address, endpoint);
+        let form = [("tags", tags.join(","))];
+
+        let res = self.send(|client| client.post(&addr).form(&form)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+
+    /// Create a category that saves its torrents to `path`
+    ///
+    /// Fails with `Error::Conflict` if the category already exists
     pub async fn add_category(&self, name: &str, path: &str) -> Result<(), error::Error> {
-        let addr = push_own!(
-            self.address,
-            "/api/v2/torrents/createCategory?savePath=",
-            path,
-            "&category=",
-            name
-        );
+        self.create_category(name, path, &CategoryDownloadPath::Default)
+            .await
+    }
 
-        dbg! {&addr};
+    /// Create a category, with the download path of WebUI API 2.8.4 (qbittorrent 4.4)
+    ///
+    /// Fails with `Error::Conflict` if the category already exists
+    pub async fn create_category(
+        &self,
+        name: &str,
+        path: &str,
+        download_path: &CategoryDownloadPath,
+    ) -> Result<(), error::Error> {
+        let form = CategoryForm::new(name, path, download_path)?;
+        form.send(self, "/api/v2/torrents/createCategory").await
+    }
 
-        let res = self
-            .client
-            .get(&addr)
-            .headers(self.make_headers()?)
-            .send()
-            .await?
-            .bytes()
-            .await?;
-        dbg! {res};
+    /// Change the paths of an existing category
+    ///
+    /// Fails with `Error::Conflict` if the category does not exist
+    pub async fn edit_category(
+        &self,
+        name: &str,
+        path: &str,
+        download_path: &CategoryDownloadPath,
+    ) -> Result<(), error::Error> {
+        let form = CategoryForm::new(name, path, download_path)?;
+        form.send(self, "/api/v2/torrents/editCategory").await
+    }
+
+    /// Remove categories, the torrents in them are left without a category
+    pub async fn remove_categories(&self, names: &[String]) -> Result<(), error::Error> {
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        let addr = push_own!(self.address, "/api/v2/torrents/removeCategories");
+        let form = [("categories", names.join("\n"))];
 
+        let res = self.send(|client| client.post(&addr).form(&form)).await?;
+        check_status(res).await?;
         Ok(())
     }
 }
+
+/// Older versions of qbittorrent answer 200 OK with `Fails.` when no torrent could be added,
+/// newer ones with 409 Conflict
+async fn check_added(res: reqwest::Response) -> Result<(), error::Error> {
+    let text = check_status(res).await?.text().await?;
+
+    if text.trim() == "Fails." {
+        Err(error::Error::Conflict(text))
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct CategoryForm<'a> {
+    category: &'a str,
+    #[serde(rename = "savePath")]
+    save_path: &'a str,
+    #[serde(
+        rename = "downloadPathEnabled",
+        skip_serializing_if = "Option::is_none"
+    )]
+    download_path_enabled: Option<bool>,
+    #[serde(rename = "downloadPath", skip_serializing_if = "Option::is_none")]
+    download_path: Option<&'a str>,
+}
+
+impl<'a> CategoryForm<'a> {
+    fn new(
+        category: &'a str,
+        save_path: &'a str,
+        download_path: &'a CategoryDownloadPath,
+    ) -> Result<Self, error::Error> {
+        if !is_valid_category(category) {
+            return Err(error::Error::InvalidCategory(category.to_string()));
+        }
+
+        let (download_path_enabled, download_path) = match download_path {
+            CategoryDownloadPath::Default => (None, None),
+            CategoryDownloadPath::Disabled => (Some(false), None),
+            CategoryDownloadPath::Path(path) => (Some(true), Some(path.as_str())),
+        };
+
+        Ok(Self {
+            category,
+            save_path,
+            download_path_enabled,
+            download_path,
+        })
+    }
+
+    async fn send(self, api: &Api, endpoint: &str) -> Result<(), error::Error> {
+        let addr = push_own!(api.address, endpoint);
+
+        let res = api.send(|client| client.post(&addr).form(&self)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+}
+
+/// The rules qbittorrent applies to category names. `/` separates subcategories
+pub(crate) fn is_valid_category(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains('\\')
+        && !name.starts_with('/')
+        && !name.ends_with('/')
+        && !name.contains("//")
+}
+
+/// Whether the WebUI API `version` (e.g. `2.8.19`) is older than `minimum`
+pub(crate) fn api_older_than(version: &str, minimum: &[u64]) -> bool {
+    let version: Vec<u64> = version
+        .trim()
+        .split('.')
+        .map(|x| x.parse().unwrap_or(0))
+        .collect();
+
+    version.as_slice() < minimum
+}
@@ -1,23 +1,36 @@
 //! Structs returned by api queries
 
-use super::utils;
+use derive_builder::Builder;
 use derive_getters::Getters;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
 
-/// Overall metadata about this qbit client
-// TODO: fix struct definitions
-#[derive(Debug, Deserialize, Getters)]
+/// Overall metadata about this qbit client, returned from `sync/maindata`
+///
+/// Apart from `rid`, fields are only sent when they changed since the `rid` the request
+/// was made with, and changed torrents / categories only carry the keys that changed. Use
+/// `sync::MainDataSync` to fold these partial updates into a complete snapshot.
+#[derive(Debug, Deserialize, Getters, Clone)]
 pub struct MainData {
     rid: u64,
+    #[serde(default)]
     full_update: bool,
-    torrents: Torrent,
+    #[serde(default)]
+    torrents: BTreeMap<String, Map<String, Value>>,
+    #[serde(default)]
     torrents_removed: Vec<String>,
-    categories: Categories,
+    #[serde(default)]
+    categories: BTreeMap<String, Map<String, Value>>,
+    #[serde(default)]
     categories_removed: Vec<String>,
+    #[serde(default)]
     tags: Vec<String>,
+    #[serde(default)]
     tags_removed: Vec<String>,
-    queueing: bool,
-    server_state: ServerState,
+    queueing: Option<bool>,
+    server_state: Option<Map<String, Value>>,
 }
 
 /// generic torrent information returned from get_all_torrents()
@@ -114,6 +127,18 @@ pub struct Torrent {
     upspeed: i64,
 }
 
+impl Torrent {
+    /// The tags of this torrent, parsed from the comma separated `tags`
+    pub fn tag_set(&self) -> BTreeSet<String> {
+        self.tags
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(String::from)
+            .collect()
+    }
+}
+
 /// Trackers associated with a torrent
 ///
 /// ```norust
@@ -126,21 +151,40 @@ pub struct Torrent {
 /// num_downloaded 	integer 	Number of completed downlods for current torrent, as reported by the tracker
 /// msg 	string 	Tracker message (there is no way of knowing what this message is - it's up to tracker admins)
 /// ```
+///
+/// The DHT, PeX and LSD pseudo-trackers (see `Tracker::kind`) report `""` or `"/"` as their
+/// tier, and every tracker reports -1 for counts it does not know. Both become `None`.
 #[derive(Serialize, Deserialize, Debug, Clone, Getters)]
 pub struct Tracker {
     url: String,
     #[getter(skip)]
     status: i32,
-    // TODO: fix this since some people do things non standard with "/" here
-    // tier: u32,
+    #[serde(default, deserialize_with = "lenient_count")]
+    tier: Option<u32>,
     num_peers: i32,
-    // TODO: documentation says these will be returned but the fields do not appear
-    // num_seeds: i32,
-    // num_leeches: i32,
-    // num_downloaded: i64,
+    #[serde(default, deserialize_with = "lenient_count")]
+    num_seeds: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_count")]
+    num_leeches: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_count")]
+    num_downloaded: Option<u64>,
     msg: String,
 }
 impl Tracker {
+    pub fn kind(&self) -> TrackerKind {
+        match self.url.as_str() {
+            "** [DHT] **" => TrackerKind::Dht,
+            "** [PeX] **" => TrackerKind::Pex,
+            "** [LSD] **" => TrackerKind::Lsd,
+            _ => TrackerKind::Url,
+        }
+    }
+
+    /// DHT, PeX and LSD are listed as trackers, but can not be edited or removed
+    pub fn is_pseudo(&self) -> bool {
+        self.kind() != TrackerKind::Url
+    }
+
     pub fn status(&self) -> TrackerStatus {
         match self.status {
             0 => TrackerStatus::TrackerDisabled,
@@ -153,6 +197,16 @@ impl Tracker {
     }
 }
 
+/// Where the peers of a `Tracker` entry come from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackerKind {
+    /// An actual tracker
+    Url,
+    Dht,
+    Pex,
+    Lsd,
+}
+
 /// Working-status tracker for a particular torrent
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum TrackerStatus {
@@ -164,6 +218,12 @@ pub enum TrackerStatus {
     UnknownResponse,
 }
 
+/// An HTTP seed of a torrent. returned from Torrent::web_seeds()
+#[derive(Serialize, Deserialize, Debug, Clone, Getters)]
+pub struct WebSeed {
+    url: String,
+}
+
 /// Metadata about a torrent. returned from Torrent::properties()
 ///
 /// ```norust
@@ -303,6 +363,17 @@ pub enum State {
     Unknown,
 }
 
+/// Progress of moving a torrent with `traits::Location`, see `Hash::move_status`
+#[derive(Debug, Clone)]
+pub enum MoveStatus {
+    /// The data is still being moved
+    Moving,
+    /// The torrent is stored at the new location
+    Moved,
+    /// The torrent stopped moving but is not stored at the new location, or is in an error state
+    Failed { state: State, save_path: String },
+}
+
 /// Transfer stats for a torrent
 ///
 /// ```norust
@@ -336,19 +407,21 @@ pub struct TransferInfo {
 /// firewalled
 /// disconnected
 /// ```
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
 pub enum ConnectionStatus {
     #[serde(rename="connected")]
     Connected,
     #[serde(rename="firewalled")]
     Firewalled,
     #[serde(rename="disconnected")]
+    #[default]
     Disconnected,
 }
 
 /// top-level torrent information
 ///
 /// ```norust
+/// index 	integer 	File index (only sent by newer versions, otherwise the position in the list)
 /// name 	string 	File name (including relative path)
 /// size 	integer 	File size (bytes)
 /// progress 	float 	File progress (percentage/100)
@@ -359,18 +432,20 @@ pub enum ConnectionStatus {
 /// ```
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct TorrentInfoSerde {
+    index: Option<usize>,
     name: String,
     size: i64,
     progress: f64,
-    priority: i16,
+    priority: FilePriority,
     is_seed: Option<bool>,
     piece_range: Vec<i64>,
     availability: f64,
 }
 impl<'a> TorrentInfoSerde {
-    pub fn into_info(self, hash: &'a Hash) -> TorrentInfo<'a> {
+    pub fn into_info(self, hash: &'a Hash, position: usize) -> TorrentInfo<'a> {
         TorrentInfo {
             hash,
+            index: self.index.unwrap_or(position),
             name: self.name,
             size: self.size,
             progress: self.progress,
@@ -419,30 +494,348 @@ pub enum AlternateLimits {
     Disabled
 }
 
+/// A download or upload speed limit
+///
+/// qbittorrent sends and accepts limits as bytes/s, where 0 (or -1) means unlimited. A limit
+/// of `BytesPerSecond(0)` is therefore the same as, and compares equal to, `Unlimited`. The
+/// constructors never create it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(from = "i64", into = "i64")]
+pub enum SpeedLimit {
+    Unlimited,
+    BytesPerSecond(u64),
+}
+
+impl SpeedLimit {
+    pub fn bytes(bytes: u64) -> Self {
+        if bytes == 0 {
+            SpeedLimit::Unlimited
+        } else {
+            SpeedLimit::BytesPerSecond(bytes)
+        }
+    }
+
+    pub fn kibibytes_per_second(kib: u64) -> Self {
+        Self::bytes(kib.saturating_mul(1024))
+    }
+
+    pub fn mebibytes_per_second(mib: u64) -> Self {
+        Self::bytes(mib.saturating_mul(1024 * 1024))
+    }
+
+    /// The limit in bytes/s, `None` if unlimited
+    pub fn bytes_per_second(&self) -> Option<u64> {
+        match self {
+            SpeedLimit::BytesPerSecond(x) if *x > 0 => Some(*x),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for SpeedLimit {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes_per_second() == other.bytes_per_second()
+    }
+}
+
+impl Eq for SpeedLimit {}
+
+impl From<i64> for SpeedLimit {
+    fn from(value: i64) -> Self {
+        if value > 0 {
+            SpeedLimit::BytesPerSecond(value as u64)
+        } else {
+            SpeedLimit::Unlimited
+        }
+    }
+}
+
+impl From<SpeedLimit> for i64 {
+    fn from(value: SpeedLimit) -> i64 {
+        match value {
+            SpeedLimit::Unlimited => 0,
+            SpeedLimit::BytesPerSecond(x) => i64::try_from(x).unwrap_or(i64::MAX),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Getters)]
 pub struct TorrentInfo<'a> {
     hash: &'a Hash,
+    index: usize,
     name: String,
     size: i64,
     progress: f64,
-    priority: i16,
+    priority: FilePriority,
     is_seed: Option<bool>,
     piece_range: Vec<i64>,
     availability: f64,
 }
 
-#[derive(Debug, Deserialize, Default, Getters)]
+/// Download priority of a single file in a torrent
+///
+/// The web UI only sets the named priorities, but libtorrent allows anything from 0 to 7 and
+/// other clients may have set one of those.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "i64", into = "i64")]
+pub enum FilePriority {
+    DoNotDownload,
+    Normal,
+    High,
+    Maximal,
+    Other(i64),
+}
+
+impl From<i64> for FilePriority {
+    fn from(value: i64) -> Self {
+        match value {
+            0 => FilePriority::DoNotDownload,
+            1 => FilePriority::Normal,
+            6 => FilePriority::High,
+            7 => FilePriority::Maximal,
+            other => FilePriority::Other(other),
+        }
+    }
+}
+
+impl From<FilePriority> for i64 {
+    fn from(value: FilePriority) -> i64 {
+        match value {
+            FilePriority::DoNotDownload => 0,
+            FilePriority::Normal => 1,
+            FilePriority::High => 6,
+            FilePriority::Maximal => 7,
+            FilePriority::Other(x) => x,
+        }
+    }
+}
+
+int_enum! {
+    /// Download state of a single piece, from `torrents/pieceStates`
+    pub enum PieceState {
+        NotDownloaded = 0,
+        Downloading = 1,
+        Downloaded = 2,
+    }
+}
+
+/// How many of the pieces of a file are downloaded, see `TorrentInfo::piece_completion`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Getters)]
+pub struct PieceCompletion {
+    total: usize,
+    downloaded: usize,
+    downloading: usize,
+}
+
+impl PieceCompletion {
+    /// Pieces that are neither downloaded nor being downloaded
+    pub fn missing(&self) -> usize {
+        self.total - self.downloaded - self.downloading
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.downloaded == self.total
+    }
+}
+
+impl<'a> TorrentInfo<'a> {
+    /// Count the states of the pieces this file spans
+    ///
+    /// `states` is the whole torrent as returned from `TorrentData::piece_states`. Pieces
+    /// at the edges of the file are shared with its neighbours, so they are counted for
+    /// both files.
+    pub fn piece_completion(&self, states: &[PieceState]) -> PieceCompletion {
+        let mut completion = PieceCompletion::default();
+
+        let (start, end) = match self.piece_range.as_slice() {
+            [start, end] if *start >= 0 && end >= start => (*start as usize, *end as usize),
+            // empty files do not span any pieces
+            _ => return completion,
+        };
+
+        for state in states.iter().take(end + 1).skip(start) {
+            completion.total += 1;
+            match state {
+                PieceState::Downloaded => completion.downloaded += 1,
+                PieceState::Downloading => completion.downloading += 1,
+                PieceState::NotDownloaded => (),
+            }
+        }
+
+        completion
+    }
+}
+
+#[derive(Debug, Deserialize, Default, Getters, Clone)]
 pub struct Categories {
     name: String,
     #[serde(rename = "savePath")]
     save_path: String,
+    /// Only sent by WebUI API 2.8.4 (qbittorrent 4.4) and later
+    #[serde(default)]
+    download_path: CategoryDownloadPath,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ServerState {}
+/// Where incomplete torrents of a category are stored
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum CategoryDownloadPath {
+    /// Follow the global download path preferences
+    #[default]
+    Default,
+    /// Store incomplete torrents in the save path
+    Disabled,
+    Path(String),
+}
 
-#[derive(Debug, Deserialize)]
-pub struct Peer {}
+/// qbittorrent leaves the key out for `Default`, and sends `false` for `Disabled`
+impl<'de> Deserialize<'de> for CategoryDownloadPath {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Enabled(bool),
+            Path(String),
+        }
+
+        let path = match Option::<Raw>::deserialize(deserializer)? {
+            Some(Raw::Enabled(false)) => CategoryDownloadPath::Disabled,
+            Some(Raw::Path(path)) => CategoryDownloadPath::Path(path),
+            Some(Raw::Enabled(true)) | None => CategoryDownloadPath::Default,
+        };
+        Ok(path)
+    }
+}
+
+/// Global client state, as reported in the `server_state` field of `sync/maindata`
+///
+/// maindata only sends the keys that changed, `sync::MainDataSync` merges them so that
+/// this is always a complete view. Keys that an older qbittorrent does not send are left
+/// at their default.
+///
+/// ```norust
+/// alltime_dl 	integer 	Total data downloaded over the lifetime of the client (bytes)
+/// alltime_ul 	integer 	Total data uploaded over the lifetime of the client (bytes)
+/// average_time_queue 	integer 	Average time an io job spends in the queue (ms)
+/// connection_status 	string 	Connection status
+/// dht_nodes 	integer 	DHT nodes connected to
+/// dl_info_data 	integer 	Data downloaded this session (bytes)
+/// dl_info_speed 	integer 	Global download rate (bytes/s)
+/// dl_rate_limit 	integer 	Download rate limit (bytes/s)
+/// free_space_on_disk 	integer 	Free space in the default save path (bytes)
+/// global_ratio 	string 	Global share ratio
+/// last_external_address_v4 	string 	Last known external IPv4 address
+/// last_external_address_v6 	string 	Last known external IPv6 address
+/// queued_io_jobs 	integer 	Number of io jobs waiting in the queue
+/// queueing 	bool 	True if torrent queueing is enabled
+/// read_cache_hits 	string 	Read cache hits (percentage)
+/// read_cache_overload 	string 	Read cache overload (percentage)
+/// refresh_interval 	integer 	WebUI refresh interval (ms)
+/// total_buffers_size 	integer 	Total size of disk buffers (bytes)
+/// total_peer_connections 	integer 	Number of connected peers
+/// total_queued_size 	integer 	Total size of queued io jobs (bytes)
+/// total_wasted_session 	integer 	Data wasted this session (bytes)
+/// up_info_data 	integer 	Data uploaded this session (bytes)
+/// up_info_speed 	integer 	Global upload rate (bytes/s)
+/// up_rate_limit 	integer 	Upload rate limit (bytes/s)
+/// use_alt_speed_limits 	bool 	True if alternative speed limits are in effect
+/// use_subcategories 	bool 	True if subcategories are enabled
+/// write_cache_overload 	string 	Write cache overload (percentage)
+/// ```
+#[derive(Debug, Deserialize, Default, Clone, Getters)]
+#[serde(default)]
+pub struct ServerState {
+    alltime_dl: u64,
+    alltime_ul: u64,
+    average_time_queue: u64,
+    connection_status: ConnectionStatus,
+    dht_nodes: u64,
+    dl_info_data: u64,
+    dl_info_speed: u64,
+    dl_rate_limit: u64,
+    free_space_on_disk: i64,
+    #[serde(deserialize_with = "f64_from_str")]
+    global_ratio: f64,
+    last_external_address_v4: String,
+    last_external_address_v6: String,
+    queued_io_jobs: u64,
+    queueing: bool,
+    #[serde(deserialize_with = "f64_from_str")]
+    read_cache_hits: f64,
+    #[serde(deserialize_with = "f64_from_str")]
+    read_cache_overload: f64,
+    refresh_interval: u64,
+    total_buffers_size: u64,
+    total_peer_connections: u64,
+    total_queued_size: u64,
+    total_wasted_session: u64,
+    up_info_data: u64,
+    up_info_speed: u64,
+    up_rate_limit: u64,
+    use_alt_speed_limits: bool,
+    use_subcategories: bool,
+    #[serde(deserialize_with = "f64_from_str")]
+    write_cache_overload: f64,
+}
+
+/// Raw response of `sync/torrentPeers`
+///
+/// Like `MainData`, only peers that changed since the requested `rid` are sent and they
+/// only carry the keys that changed. `sync::PeersSync` merges them into full `Peer`s.
+#[derive(Debug, Deserialize, Getters, Clone)]
+pub struct TorrentPeers {
+    rid: u64,
+    #[serde(default)]
+    full_update: bool,
+    #[serde(default)]
+    peers: BTreeMap<String, Map<String, Value>>,
+    #[serde(default)]
+    peers_removed: Vec<String>,
+    show_flags: Option<bool>,
+}
+
+/// A peer connected to a torrent
+///
+/// ```norust
+/// client 	string 	Client name and version
+/// connection 	string 	Connection type (BT / uTP / Web)
+/// country 	string 	Country name (only sent when peer country resolution is enabled)
+/// country_code 	string 	Two letter country code (only sent when peer country resolution is enabled)
+/// dl_speed 	integer 	Download rate from this peer (bytes/s)
+/// downloaded 	integer 	Data downloaded from this peer (bytes)
+/// files 	string 	Files of the torrent this peer is transferring, separated by newlines
+/// flags 	string 	Peer flags, separated by spaces
+/// flags_desc 	string 	Description of each of the flags
+/// ip 	string 	Peer ip address
+/// peer_id_client 	string 	Client name derived from the peer id
+/// port 	integer 	Peer port
+/// progress 	float 	Peer progress (percentage/100)
+/// relevance 	float 	How much of what this peer has we are missing (percentage/100)
+/// up_speed 	integer 	Upload rate to this peer (bytes/s)
+/// uploaded 	integer 	Data uploaded to this peer (bytes)
+/// ```
+#[derive(Debug, Deserialize, Default, Clone, Getters)]
+#[serde(default)]
+pub struct Peer {
+    client: String,
+    connection: String,
+    country: String,
+    country_code: String,
+    dl_speed: u64,
+    downloaded: u64,
+    files: String,
+    flags: String,
+    flags_desc: String,
+    ip: String,
+    peer_id_client: String,
+    port: u16,
+    progress: f64,
+    relevance: f64,
+    up_speed: u64,
+    uploaded: u64,
+}
 
 #[derive(Debug, Deserialize, Getters)]
 pub struct BuildInfo {
@@ -453,8 +846,654 @@ pub struct BuildInfo {
     bitness: String,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct Preferences {}
+/// Application preferences, as returned by `app/preferences`
+///
+/// Every key is optional: the set of keys differs between qbittorrent versions and
+/// `Api::set_preferences` only sends the keys that are `Some`. Build a partial update with
+/// `PreferencesBuilder`:
+///
+/// ```ignore
+/// let update = PreferencesBuilder::default()
+///     .dl_limit(1_000_000)
+///     .scheduler_days(SchedulerDays::Weekdays)
+///     .build()
+///     .unwrap();
+/// api.set_preferences(&update).await?;
+/// ```
+///
+/// Field names and meaning follow the [WebUI API documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-4.1)#get-application-preferences)
+#[derive(Debug, Clone, Default, Deserialize, Serialize, Builder, Getters)]
+#[builder(setter(into, strip_option), default)]
+pub struct Preferences {
+    // behaviour
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locale: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    performance_warning: Option<bool>,
+
+    // downloads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    create_subfolder_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    torrent_content_layout: Option<ContentLayout>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_paused_enabled: Option<bool>,
+    /// What to do with the .torrent file after it was added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_delete_mode: Option<AutoDeleteMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    preallocate_all: Option<bool>,
+    /// Append .!qB to incomplete files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    incomplete_files_ext: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_tmm_enabled: Option<bool>,
+    /// Relocate the torrent when its category changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    torrent_changed_tmm_enabled: Option<bool>,
+    /// Relocate affected torrents when the default save path changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    save_path_changed_tmm_enabled: Option<bool>,
+    /// Relocate affected torrents when their category's save path changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category_changed_tmm_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    use_subcategories: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    save_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temp_path_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temp_path: Option<String>,
+    /// Watched folders and where torrents found in them are downloaded to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scan_dirs: Option<BTreeMap<String, ScanDirTarget>>,
+    /// Copy .torrent files to this directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    export_dir: Option<String>,
+    /// Copy .torrent files of finished downloads to this directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    export_dir_fin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    excluded_file_names_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    excluded_file_names: Option<String>,
+
+    // email notification on download completion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mail_notification_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mail_notification_sender: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mail_notification_email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mail_notification_smtp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mail_notification_ssl_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mail_notification_auth_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mail_notification_username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mail_notification_password: Option<String>,
+
+    // external program on download completion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    autorun_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    autorun_program: Option<String>,
+
+    // queueing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    queueing_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_active_downloads: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_active_torrents: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_active_uploads: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dont_count_slow_torrents: Option<bool>,
+    /// Download rate threshold (KiB/s) for a torrent to be considered slow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slow_torrent_dl_rate_threshold: Option<i64>,
+    /// Upload rate threshold (KiB/s) for a torrent to be considered slow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slow_torrent_ul_rate_threshold: Option<i64>,
+    /// Seconds a torrent must be inactive before it is considered slow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slow_torrent_inactive_timer: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    add_to_top_of_queue: Option<bool>,
+
+    // seeding limits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_ratio_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_ratio: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_ratio_act: Option<MaxRatioAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_seeding_time_enabled: Option<bool>,
+    /// Minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_seeding_time: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_inactive_seeding_time_enabled: Option<bool>,
+    /// Minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_inactive_seeding_time: Option<i64>,
+
+    // connection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    listen_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upnp: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    random_port: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_connec: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_connec_per_torrent: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_uploads: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_uploads_per_torrent: Option<i64>,
+    /// Seconds to wait for trackers to acknowledge a stopped announce on shutdown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_tracker_timeout: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_piece_extent_affinity: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bittorrent_protocol: Option<BittorrentProtocol>,
+
+    // speed
+    /// Global download limit (bytes/s)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dl_limit: Option<i64>,
+    /// Global upload limit (bytes/s)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    up_limit: Option<i64>,
+    /// Alternative global download limit (bytes/s)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alt_dl_limit: Option<i64>,
+    /// Alternative global upload limit (bytes/s)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alt_up_limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit_utp_rate: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit_tcp_overhead: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit_lan_peers: Option<bool>,
+
+    // alternative speed limit scheduler
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scheduler_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schedule_from_hour: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schedule_from_min: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schedule_to_hour: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schedule_to_min: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scheduler_days: Option<SchedulerDays>,
+
+    // privacy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dht: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pex: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lsd: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encryption: Option<Encryption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    anonymous_mode: Option<bool>,
+
+    // proxy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxy_type: Option<ProxyType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxy_ip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxy_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxy_peer_connections: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxy_auth_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxy_username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxy_password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxy_torrents_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxy_hostname_lookup: Option<bool>,
+
+    // ip filtering
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ip_filter_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ip_filter_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ip_filter_trackers: Option<bool>,
+    /// Manually banned ip addresses, separated by newlines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "banned_IPs")]
+    banned_ips: Option<String>,
+
+    // web ui
+    /// Domains accepted by the host header validation, separated by `;`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_domain_list: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_upnp: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_username: Option<String>,
+    /// Write only, never returned by `get_preferences`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_csrf_protection_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_clickjacking_protection_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_secure_cookie_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_max_auth_fail_count: Option<i64>,
+    /// Seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_ban_duration: Option<i64>,
+    /// Seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_session_timeout: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_host_header_validation_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bypass_local_auth: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bypass_auth_subnet_whitelist_enabled: Option<bool>,
+    /// Subnets that skip authentication, separated by newlines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bypass_auth_subnet_whitelist: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alternative_webui_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alternative_webui_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    use_https: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_https_key_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_https_cert_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_use_custom_http_headers_enabled: Option<bool>,
+    /// Extra response headers, one `Name: value` per line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_ui_custom_http_headers: Option<String>,
+
+    // dynamic dns
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dyndns_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dyndns_service: Option<DynDnsService>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dyndns_username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dyndns_password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dyndns_domain: Option<String>,
+
+    // rss
+    /// Minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rss_refresh_interval: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rss_max_articles_per_feed: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rss_processing_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rss_auto_downloading_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rss_download_repack_proper_episodes: Option<bool>,
+    /// Filters, separated by newlines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rss_smart_episode_filters: Option<String>,
+
+    // trackers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    add_trackers_enabled: Option<bool>,
+    /// Trackers appended to new downloads, separated by newlines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    add_trackers: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    announce_ip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    announce_to_all_tiers: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    announce_to_all_trackers: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_embedded_tracker: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    embedded_tracker_port: Option<u16>,
+
+    // advanced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    async_io_threads: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hashing_threads: Option<i64>,
+    /// MiB
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checking_memory_use: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_interface_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_network_interface: Option<String>,
+    /// MiB, -1 for automatic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disk_cache: Option<i64>,
+    /// Seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disk_cache_ttl: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_coalesce_read_write: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_multi_connections_from_same_ip: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_os_cache: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_upload_suggestions: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_pool_size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outgoing_ports_max: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outgoing_ports_min: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recheck_completed_torrents: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolve_peer_countries: Option<bool>,
+    /// Minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    save_resume_data_interval: Option<i64>,
+    /// KiB
+    #[serde(skip_serializing_if = "Option::is_none")]
+    send_buffer_low_watermark: Option<i64>,
+    /// KiB
+    #[serde(skip_serializing_if = "Option::is_none")]
+    send_buffer_watermark: Option<i64>,
+    /// Percent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    send_buffer_watermark_factor: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    socket_backlog_size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upload_choking_algorithm: Option<UploadChokingAlgorithm>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upload_slots_behavior: Option<UploadSlotsBehavior>,
+    /// Seconds, 0 for permanent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upnp_lease_duration: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    utp_tcp_mixed_mode: Option<UtpTcpMixedMode>,
+}
+
+impl Preferences {
+    /// The json sent by `Api::set_preferences`
+    ///
+    /// `legacy_proxy` servers (before qbittorrent 4.6) only understand the integer proxy
+    /// codes, where authentication is part of the proxy type. Newer ones only understand the
+    /// names and `proxy_auth_enabled`.
+    pub(crate) fn to_json(&self, legacy_proxy: bool) -> Result<Value, serde_json::Error> {
+        let mut json = serde_json::to_value(self)?;
+
+        if let Some(proxy) = self.proxy_type {
+            let auth = self
+                .proxy_auth_enabled
+                .unwrap_or_else(|| proxy.is_authenticated());
+
+            if legacy_proxy {
+                json["proxy_type"] = Value::from(proxy.legacy_code(auth));
+            } else {
+                json["proxy_type"] = Value::from(proxy.name());
+                if proxy.is_authenticated() {
+                    json["proxy_auth_enabled"] = Value::from(auth);
+                }
+            }
+        }
+
+        Ok(json)
+    }
+}
+
+/// How the files of a new torrent are laid out on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ContentLayout {
+    Original,
+    Subfolder,
+    NoSubfolder,
+}
+
+int_enum! {
+    /// What happens to a .torrent file after it was added
+    pub enum AutoDeleteMode {
+        Never = 0,
+        IfAdded = 1,
+        Always = 2,
+    }
+}
+
+int_enum! {
+    /// Action taken once a torrent reaches its share limit
+    pub enum MaxRatioAction {
+        Pause = 0,
+        Remove = 1,
+        EnableSuperSeeding = 2,
+        RemoveWithFiles = 3,
+    }
+}
+
+int_enum! {
+    pub enum BittorrentProtocol {
+        TcpAndUtp = 0,
+        Tcp = 1,
+        Utp = 2,
+    }
+}
+
+int_enum! {
+    /// Days on which the alternative speed limit scheduler is active
+    pub enum SchedulerDays {
+        EveryDay = 0,
+        Weekdays = 1,
+        Weekends = 2,
+        Monday = 3,
+        Tuesday = 4,
+        Wednesday = 5,
+        Thursday = 6,
+        Friday = 7,
+        Saturday = 8,
+        Sunday = 9,
+    }
+}
+
+int_enum! {
+    /// Peer connection encryption policy
+    pub enum Encryption {
+        Prefer = 0,
+        ForceOn = 1,
+        ForceOff = 2,
+    }
+}
+
+int_enum! {
+    pub enum DynDnsService {
+        DynDns = 0,
+        NoIp = 1,
+    }
+}
+
+int_enum! {
+    pub enum UploadChokingAlgorithm {
+        RoundRobin = 0,
+        FastestUpload = 1,
+        AntiLeech = 2,
+    }
+}
+
+int_enum! {
+    pub enum UploadSlotsBehavior {
+        FixedSlots = 0,
+        UploadRateBased = 1,
+    }
+}
+
+int_enum! {
+    pub enum UtpTcpMixedMode {
+        PreferTcp = 0,
+        PeerProportional = 1,
+    }
+}
+
+/// Kind of proxy used for connections
+///
+/// qbittorrent before 4.6 sends an integer code, where authentication is part of the proxy
+/// type, newer versions send a string and use `proxy_auth_enabled` instead. Both are
+/// accepted, `Api::set_preferences` sends the form the server understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyType {
+    None,
+    Http,
+    Socks5,
+    Socks4,
+    HttpWithAuth,
+    Socks5WithAuth,
+}
+
+impl ProxyType {
+    fn is_authenticated(&self) -> bool {
+        matches!(self, ProxyType::HttpWithAuth | ProxyType::Socks5WithAuth)
+    }
+
+    /// The name used by qbittorrent 4.6 and later
+    fn name(&self) -> &'static str {
+        match self {
+            ProxyType::None => "None",
+            ProxyType::Http | ProxyType::HttpWithAuth => "HTTP",
+            ProxyType::Socks5 | ProxyType::Socks5WithAuth => "SOCKS5",
+            ProxyType::Socks4 => "SOCKS4",
+        }
+    }
+
+    /// The code used before qbittorrent 4.6, SOCKS4 has no authenticated variant
+    fn legacy_code(&self, auth: bool) -> i64 {
+        match (self, auth) {
+            (ProxyType::None, _) => 0,
+            (ProxyType::Http, false) | (ProxyType::HttpWithAuth, false) => 1,
+            (ProxyType::Socks5, false) | (ProxyType::Socks5WithAuth, false) => 2,
+            (ProxyType::Http, true) | (ProxyType::HttpWithAuth, true) => 3,
+            (ProxyType::Socks5, true) | (ProxyType::Socks5WithAuth, true) => 4,
+            (ProxyType::Socks4, _) => 5,
+        }
+    }
+}
+
+impl Serialize for ProxyType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let proxy = match self {
+            ProxyType::None => "None",
+            ProxyType::Http => "HTTP",
+            ProxyType::Socks5 => "SOCKS5",
+            ProxyType::Socks4 => "SOCKS4",
+            ProxyType::HttpWithAuth => return serializer.serialize_i64(3),
+            ProxyType::Socks5WithAuth => return serializer.serialize_i64(4),
+        };
+        serializer.serialize_str(proxy)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProxyType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum CodeOrName {
+            Code(i64),
+            Name(String),
+        }
+
+        match CodeOrName::deserialize(deserializer)? {
+            CodeOrName::Code(-1) | CodeOrName::Code(0) => Ok(ProxyType::None),
+            CodeOrName::Code(1) => Ok(ProxyType::Http),
+            CodeOrName::Code(2) => Ok(ProxyType::Socks5),
+            CodeOrName::Code(3) => Ok(ProxyType::HttpWithAuth),
+            CodeOrName::Code(4) => Ok(ProxyType::Socks5WithAuth),
+            CodeOrName::Code(5) => Ok(ProxyType::Socks4),
+            CodeOrName::Name(name) => match name.as_str() {
+                "None" => Ok(ProxyType::None),
+                "HTTP" => Ok(ProxyType::Http),
+                "SOCKS5" => Ok(ProxyType::Socks5),
+                "SOCKS4" => Ok(ProxyType::Socks4),
+                _ => Err(serde::de::Error::custom(format!(
+                    "unknown proxy type {}",
+                    name
+                ))),
+            },
+            CodeOrName::Code(code) => Err(serde::de::Error::custom(format!(
+                "unknown proxy type {}",
+                code
+            ))),
+        }
+    }
+}
+
+/// Where torrents found in a watched folder are downloaded to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanDirTarget {
+    /// Download into the watched folder itself
+    MonitoredFolder,
+    /// Download into the default save path
+    DefaultSavePath,
+    /// Download into this path
+    Path(String),
+}
+
+impl Serialize for ScanDirTarget {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ScanDirTarget::MonitoredFolder => serializer.serialize_i64(0),
+            ScanDirTarget::DefaultSavePath => serializer.serialize_i64(1),
+            ScanDirTarget::Path(path) => serializer.serialize_str(path),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ScanDirTarget {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum CodeOrPath {
+            Code(i64),
+            Path(String),
+        }
+
+        match CodeOrPath::deserialize(deserializer)? {
+            CodeOrPath::Code(0) => Ok(ScanDirTarget::MonitoredFolder),
+            CodeOrPath::Code(1) => Ok(ScanDirTarget::DefaultSavePath),
+            CodeOrPath::Code(code) => Err(serde::de::Error::custom(format!(
+                "unknown scan dir target {}",
+                code
+            ))),
+            CodeOrPath::Path(path) => Ok(ScanDirTarget::Path(path)),
+        }
+    }
+}
 
 #[derive(Deserialize, Debug, Getters)]
 pub struct Log {
@@ -464,7 +1503,11 @@ pub struct Log {
     r#type: u64,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, Default, Hash)]
+/// Selects every torrent, for endpoints that accept `hashes=all`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllTorrents;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, Hash, PartialEq, Eq)]
 #[serde(transparent)]
 pub struct Hash {
     pub(crate) hash: String,
@@ -488,21 +1531,46 @@ impl std::ops::Deref for Hash {
     }
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub(crate) struct SerdeHashes<T: Serialize> {
-    pub(crate) hashes: T,
-}
+/// Some numeric fields (ratios, cache percentages) are sent as strings
+fn f64_from_str<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(f64),
+        String(String),
+    }
 
-impl<'a> From<&'a Hash> for SerdeHashes<&'a String> {
-    fn from(e: &'a Hash) -> Self {
-        SerdeHashes { hashes: e }
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(num) => Ok(num),
+        NumberOrString::String(string) if string.is_empty() => Ok(0.),
+        NumberOrString::String(string) => string.parse().map_err(serde::de::Error::custom),
     }
 }
 
-impl<'a> From<&'a Vec<Hash>> for SerdeHashes<String> {
-    fn from(hashes: &'a Vec<Hash>) -> Self {
-        Self {
-            hashes: utils::QueryConcat::query_concat(&hashes.as_slice(), '|'),
-        }
+/// Counts and tiers that may be negative ("unknown") or sent as strings such as `""` or `"/"`
+fn lenient_count<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: std::convert::TryFrom<i64>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(i64),
+        String(String),
     }
+
+    let number = match Option::<NumberOrString>::deserialize(deserializer)? {
+        Some(NumberOrString::Number(num)) => num,
+        Some(NumberOrString::String(string)) => match string.trim().parse() {
+            Ok(num) => num,
+            Err(_) => return Ok(None),
+        },
+        None => return Ok(None),
+    };
+
+    Ok(T::try_from(number).ok())
 }
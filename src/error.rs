@@ -1,6 +1,3 @@
-use reqwest;
-use serde_json;
-
 use thiserror::Error as ThisError;
 
 #[derive(ThisError, Debug)]
@@ -23,4 +20,22 @@ pub enum Error {
     SliceError,
     #[error("Bad response from server")]
     BadResponse,
+    #[error("Login failed, the username or password is incorrect")]
+    LoginFailed,
+    #[error("Login refused, this ip is banned for too many failed login attempts")]
+    Banned,
+    #[error("qbittorrent rejected the session (403 Forbidden) and it could not be renewed")]
+    Forbidden,
+    #[error("qbittorrent denied access (403 Forbidden): {0}")]
+    AccessDenied(String),
+    #[error("qbittorrent rejected the request as invalid (400 Bad Request): {0}")]
+    BadRequest(String),
+    #[error("The torrent could not be found (404 Not Found): {0}")]
+    NotFound(String),
+    #[error("qbittorrent could not apply the request (409 Conflict): {0}")]
+    Conflict(String),
+    #[error("Invalid category name: {0:?}")]
+    InvalidCategory(String),
+    #[error("Could not read file: {0}")]
+    Io(#[from] std::io::Error),
 }
@@ -1,5 +1,6 @@
 #![doc = include_str!("../README.md")]
 #![allow(unused_variables)]
+#![allow(clippy::tabs_in_doc_comments)]
 
 #[macro_use]
 pub(crate) mod macros;
@@ -8,11 +9,15 @@ pub(crate) mod utils;
 mod api;
 pub mod data;
 mod error;
+pub mod migration;
 pub mod queries;
+pub mod queue;
+pub mod reconcile;
+pub mod sync;
 pub mod traits;
 
 #[cfg(test)]
 pub(crate) mod tests;
 
-pub use api::Api;
+pub use api::{Api, ApiBuilder};
 pub use error::Error;
@@ -27,3 +27,39 @@ macro_rules! push_own {
         }
     };
 }
+
+// Define a fieldless enum that is (de)serialized as one of the integer codes qbittorrent uses
+macro_rules! int_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $( $(#[$variant_meta:meta])* $variant:ident = $value:expr ),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        #[serde(try_from = "i64", into = "i64")]
+        $vis enum $name {
+            $( $(#[$variant_meta])* $variant ),+
+        }
+
+        impl From<$name> for i64 {
+            fn from(value: $name) -> i64 {
+                match value {
+                    $( $name::$variant => $value ),+
+                }
+            }
+        }
+
+        impl std::convert::TryFrom<i64> for $name {
+            type Error = String;
+
+            fn try_from(value: i64) -> Result<Self, Self::Error> {
+                match value {
+                    $( x if x == $value => Ok($name::$variant), )+
+                    other => Err(format!("unknown {} value {}", stringify!($name), other)),
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,238 @@
+//! Bulk rewriting of tracker urls across every torrent
+//!
+//! ```ignore
+//! let migration = TrackerMigration::new(
+//!     TrackerMatch::Host("tracker.old-domain.org".into()),
+//!     TrackerRewrite::Host("tracker.new-domain.org".into()),
+//! )
+//! .dry_run(true);
+//!
+//! for report in migration.run(&api).await? {
+//!     println!("{}: {:?}", report.name(), report.changes());
+//! }
+//! ```
+
+use derive_getters::Getters;
+use reqwest::Url;
+
+use super::api::Api;
+use super::data::Hash;
+use super::error::Error;
+use super::traits::{TorrentData, Trackers};
+
+/// Which tracker urls a migration applies to
+#[derive(Debug, Clone)]
+pub enum TrackerMatch {
+    /// The host of the url is exactly this (case insensitive)
+    Host(String),
+    /// The url contains this string
+    Contains(String),
+}
+
+impl TrackerMatch {
+    pub fn matches(&self, url: &str) -> bool {
+        match self {
+            TrackerMatch::Host(host) => match Url::parse(url) {
+                Ok(parsed) => parsed
+                    .host_str()
+                    .map(|x| x.eq_ignore_ascii_case(host))
+                    .unwrap_or(false),
+                // DHT / PeX / LSD pseudo-trackers are not urls
+                Err(_) => false,
+            },
+            TrackerMatch::Contains(pattern) => url.contains(pattern.as_str()),
+        }
+    }
+}
+
+/// How a matching tracker url is rewritten
+pub enum TrackerRewrite {
+    /// Swap the host, keeping the scheme, port, path and query (and so the passkey)
+    Host(String),
+    /// Replace every occurrence of `from`, e.g. an old passkey with a new one
+    Replace { from: String, to: String },
+    /// Any other rewrite
+    Custom(Box<dyn Fn(&str) -> String + Send + Sync>),
+}
+
+impl std::fmt::Debug for TrackerRewrite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrackerRewrite::Host(host) => f.debug_tuple("Host").field(host).finish(),
+            TrackerRewrite::Replace { from, to } => f
+                .debug_struct("Replace")
+                .field("from", from)
+                .field("to", to)
+                .finish(),
+            TrackerRewrite::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+impl TrackerRewrite {
+    /// The rewritten url, `None` if it can not be rewritten
+    pub fn apply(&self, url: &str) -> Option<String> {
+        let new_url = match self {
+            TrackerRewrite::Host(host) => {
+                // only the host is swapped in the original string, parsing and printing the
+                // url would normalize the rest of it, e.g. drop an explicit default port
+                Url::parse(url).ok()?;
+                let (start, end) = host_span(url)?;
+                Some(format!("{}{}{}", &url[..start], host, &url[end..]))
+            }
+            TrackerRewrite::Replace { from, .. } if from.is_empty() => None,
+            TrackerRewrite::Replace { from, to } => Some(url.replace(from.as_str(), to)),
+            TrackerRewrite::Custom(rewrite) => Some(rewrite(url)),
+        };
+
+        // never send something that is not a url to the server
+        new_url.filter(|x| Url::parse(x).is_ok())
+    }
+}
+
+/// Byte range of the host in `url`, between the user info and the port
+fn host_span(url: &str) -> Option<(usize, usize)> {
+    let authority_start = url.find("://")? + 3;
+    let authority_end = url[authority_start..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |i| authority_start + i);
+    let authority = &url[authority_start..authority_end];
+
+    let host_start = authority.rfind('@').map_or(0, |i| i + 1);
+    let host = &authority[host_start..];
+    let host_len = if host.starts_with('[') {
+        // ipv6 address, its colons are not a port
+        host.find(']')? + 1
+    } else {
+        host.find(':').unwrap_or(host.len())
+    };
+
+    Some((
+        authority_start + host_start,
+        authority_start + host_start + host_len,
+    ))
+}
+
+/// Outcome of a single tracker url change
+#[derive(Debug)]
+pub enum ChangeStatus {
+    /// Computed in dry run mode, nothing was sent
+    Planned,
+    Applied,
+    Failed(Error),
+}
+
+#[derive(Debug, Getters)]
+pub struct TrackerChange {
+    from: String,
+    to: String,
+    status: ChangeStatus,
+}
+
+/// Every tracker change made (or planned) for one torrent
+#[derive(Debug, Getters)]
+pub struct TrackerReport {
+    hash: Hash,
+    name: String,
+    changes: Vec<TrackerChange>,
+    /// The trackers of the torrent could not be fetched, so nothing was changed
+    error: Option<Error>,
+}
+
+/// Rewrite the matching tracker urls of every torrent in the client
+#[derive(Debug)]
+pub struct TrackerMigration {
+    matcher: TrackerMatch,
+    rewrite: TrackerRewrite,
+    dry_run: bool,
+}
+
+impl TrackerMigration {
+    pub fn new(matcher: TrackerMatch, rewrite: TrackerRewrite) -> Self {
+        Self {
+            matcher,
+            rewrite,
+            dry_run: false,
+        }
+    }
+
+    /// Only compute the changes, without sending any edits
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// The new url for `url`, `None` if it does not match or would not change
+    pub fn rewrite_url(&self, url: &str) -> Option<String> {
+        if !self.matcher.matches(url) {
+            return None;
+        }
+
+        self.rewrite.apply(url).filter(|new_url| new_url != url)
+    }
+
+    /// Scan the trackers of every torrent and rewrite the matching ones
+    ///
+    /// A failed edit, or a torrent whose trackers can not be fetched, is recorded in the
+    /// report and does not stop the migration. Torrents without any matching tracker are left
+    /// out of the report.
+    pub async fn run(&self, api: &Api) -> Result<Vec<TrackerReport>, Error> {
+        let mut reports = Vec::new();
+
+        for torrent in api.get_torrent_list().await? {
+            let mut changes = Vec::new();
+
+            // e.g. the torrent was removed since the list was fetched
+            let trackers = match torrent.trackers(api).await {
+                Ok(trackers) => trackers,
+                Err(e) => {
+                    reports.push(TrackerReport {
+                        hash: torrent.hash().clone(),
+                        name: torrent.name().clone(),
+                        changes,
+                        error: Some(e),
+                    });
+                    continue;
+                }
+            };
+
+            for tracker in trackers {
+                if tracker.is_pseudo() {
+                    continue;
+                }
+
+                let from = tracker.url();
+                let to = match self.rewrite_url(from) {
+                    Some(to) => to,
+                    None => continue,
+                };
+
+                let status = if self.dry_run {
+                    ChangeStatus::Planned
+                } else {
+                    match torrent.edit_tracker(api, from, &to).await {
+                        Ok(()) => ChangeStatus::Applied,
+                        Err(e) => ChangeStatus::Failed(e),
+                    }
+                };
+
+                changes.push(TrackerChange {
+                    from: from.clone(),
+                    to,
+                    status,
+                });
+            }
+
+            if !changes.is_empty() {
+                reports.push(TrackerReport {
+                    hash: torrent.hash().clone(),
+                    name: torrent.name().clone(),
+                    changes,
+                    error: None,
+                });
+            }
+        }
+
+        Ok(reports)
+    }
+}
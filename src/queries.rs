@@ -10,6 +10,10 @@ use serde::{Deserialize, Serialize};
 
 use super::api::Api;
 
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use std::time::Duration;
+
 /// Getting log information
 #[derive(Debug, Builder, Default)]
 pub struct LogRequest {
@@ -62,7 +66,7 @@ pub struct TorrentRequest {
 impl TorrentRequest {
     // TODO: swap this to www_url_encoding crate
     fn url(&self) -> Result<String, error::Error> {
-        let url = serde_urlencoded::to_string(&self)?;
+        let url = serde_urlencoded::to_string(self)?;
         Ok(url)
     }
     pub async fn send(self, api: &Api) -> Result<Vec<Torrent>, Error> {
@@ -70,22 +74,15 @@ impl TorrentRequest {
 
         match self.url() {
             Ok(addition) => {
-                if addition != "".to_string() {
+                if !addition.is_empty() {
                     addr.push('?');
                     addr.push_str(&addition);
                 }
             }
-            Err(e) => return Err(Error::from(e)),
+            Err(e) => return Err(e),
         }
 
-        let res = api
-            .client
-            .get(&addr)
-            .headers(api.make_headers()?)
-            .send()
-            .await?
-            .bytes()
-            .await?;
+        let res = api.send(|client| client.get(&addr)).await?.bytes().await?;
 
         let torrents: Vec<Torrent> = serde_json::from_slice(&res)?;
 
@@ -93,10 +90,11 @@ impl TorrentRequest {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 /// Filtering enum for use in making a `TorrentRequest`
 pub enum TorrentFilter {
     #[serde(rename = "all")]
+    #[default]
     All,
     #[serde(rename = "downloading")]
     Downloading,
@@ -108,18 +106,12 @@ pub enum TorrentFilter {
     Active,
 }
 
-impl Default for TorrentFilter {
-    fn default() -> Self {
-        Self::All
-    }
-}
-
 /// Metadata for downloading magnet links and torrent files
 ///
 /// NOTE: You must include either a `urls` field or `torrents` field
 ///
 /// urls 	string 	URLs separated with newlines
-/// torrents 	raw 	Raw data of torrent file. torrents can be presented multiple times.
+/// torrents 	raw 	Raw data of torrent file. torrents can be presented multiple times. See `TorrentFile`
 /// savepath optional 	string 	Download folder
 /// cookie optional 	string 	Cookie sent to download the .torrent file
 /// category optional 	string 	Category for the torrent
@@ -137,8 +129,10 @@ impl Default for TorrentFilter {
 pub struct TorrentDownload {
     #[builder(default)]
     urls: Option<String>,
-    #[builder(default)]
-    torrents: Option<Vec<u8>>,
+    /// Uploaded as file parts of a multipart request, not with the other fields
+    #[builder(default, setter(custom))]
+    #[serde(skip)]
+    torrents: Vec<TorrentFile>,
     #[builder(default)]
     savepath: Option<String>,
     #[builder(default)]
@@ -172,6 +166,134 @@ pub struct TorrentDownload {
 
 impl TorrentDownload {
     pub async fn download(&self, api: &Api) -> Result<(), error::Error> {
-        api.add_new_torrent(&self).await
+        api.add_new_torrent(self).await
+    }
+
+    pub(crate) fn torrent_files(&self) -> &[TorrentFile] {
+        &self.torrents
+    }
+}
+
+impl TorrentDownloadBuilder {
+    /// Upload these .torrent files, replacing any added before
+    pub fn torrents<T: Into<Vec<TorrentFile>>>(&mut self, torrents: T) -> &mut Self {
+        self.torrents = Some(torrents.into());
+        self
+    }
+
+    /// Upload one more .torrent file
+    pub fn torrent_file(&mut self, torrent: TorrentFile) -> &mut Self {
+        self.torrents.get_or_insert_with(Vec::new).push(torrent);
+        self
+    }
+}
+
+/// A .torrent file to upload with `TorrentDownload`
+#[derive(Debug, Clone)]
+pub enum TorrentFile {
+    /// The contents of a .torrent file, `name` is the file name sent along with it
+    Bytes { name: String, data: Vec<u8> },
+    /// A .torrent file on disk, read when the request is sent
+    Path(PathBuf),
+}
+
+impl TorrentFile {
+    pub fn from_bytes<N: Into<String>, D: Into<Vec<u8>>>(name: N, data: D) -> Self {
+        TorrentFile::Bytes {
+            name: name.into(),
+            data: data.into(),
+        }
+    }
+
+    pub fn from_path<P: Into<PathBuf>>(path: P) -> Self {
+        TorrentFile::Path(path.into())
+    }
+
+    /// The file name and contents to upload
+    pub(crate) fn load(&self) -> Result<(String, Vec<u8>), error::Error> {
+        match self {
+            TorrentFile::Bytes { name, data } => Ok((name.clone(), data.clone())),
+            TorrentFile::Path(path) => {
+                let name = path
+                    .file_name()
+                    .map(|x| x.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "file.torrent".to_string());
+                let data = std::fs::read(path)?;
+                Ok((name, data))
+            }
+        }
+    }
+}
+
+/// A share limit of a single torrent, once it is reached the torrent stops seeding
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ShareLimit<T> {
+    /// Follow the global limit from the preferences
+    #[default]
+    Global,
+    Unlimited,
+    Limit(T),
+}
+
+impl ShareLimit<f64> {
+    fn ratio(&self) -> f64 {
+        match self {
+            ShareLimit::Global => -2.0,
+            ShareLimit::Unlimited => -1.0,
+            ShareLimit::Limit(ratio) => *ratio,
+        }
+    }
+}
+
+impl ShareLimit<Duration> {
+    /// qbittorrent only accepts whole minutes, a limit is rounded up so that it never
+    /// becomes 0 (stop seeding right away)
+    fn minutes(&self) -> i64 {
+        match self {
+            ShareLimit::Global => -2,
+            ShareLimit::Unlimited => -1,
+            ShareLimit::Limit(time) => {
+                let secs = time.as_secs() + u64::from(time.subsec_nanos() > 0);
+                let minutes = secs / 60 + u64::from(secs % 60 > 0);
+                i64::try_from(minutes).unwrap_or(i64::MAX)
+            }
+        }
+    }
+}
+
+/// Ratio and seeding time limits for `traits::ShareLimits::set_share_limits`
+///
+/// Every limit that is not set follows the global limit.
+#[derive(Debug, Builder, Clone, Default)]
+pub struct ShareLimitsRequest {
+    #[builder(default)]
+    ratio: ShareLimit<f64>,
+    #[builder(default)]
+    seeding_time: ShareLimit<Duration>,
+    /// Seeding time without any upload. Only supported by WebUI API 2.9.2
+    /// (qbittorrent 4.6) and later, older versions ignore it
+    #[builder(default)]
+    inactive_seeding_time: ShareLimit<Duration>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ShareLimitsForm<'a> {
+    hashes: &'a str,
+    #[serde(rename = "ratioLimit")]
+    ratio_limit: f64,
+    #[serde(rename = "seedingTimeLimit")]
+    seeding_time_limit: i64,
+    #[serde(rename = "inactiveSeedingTimeLimit")]
+    inactive_seeding_time_limit: i64,
+}
+
+impl ShareLimitsRequest {
+    pub(crate) fn form<'a>(&self, hashes: &'a str) -> ShareLimitsForm<'a> {
+        ShareLimitsForm {
+            hashes,
+            ratio_limit: self.ratio.ratio(),
+            seeding_time_limit: self.seeding_time.minutes(),
+            inactive_seeding_time_limit: self.inactive_seeding_time.minutes(),
+        }
     }
 }
@@ -0,0 +1,133 @@
+//! Reordering the download queue to match a desired order
+//!
+//! ```ignore
+//! // keep the season in episode order, wherever it sits in the queue
+//! let moves = api.reorder_queue(&[episode_1, episode_2, episode_3]).await?;
+//! println!("took {} moves", moves.len());
+//!
+//! // promote the urgent downloads to the head of the queue, in this order
+//! api.promote_queue(&[urgent_a, urgent_b]).await?;
+//! ```
+
+use super::api::Api;
+use super::data::{Hash, Torrent};
+use super::error::Error;
+use super::traits::Queue;
+
+/// A single request sent while reordering the queue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueueMove {
+    Top(Hash),
+    Bottom(Hash),
+}
+
+/// The fewest top / bottom moves that put the queued torrents of `order` in that order
+///
+/// Positions are taken from `Torrent::priority` of `torrents`. The longest run of `order`
+/// that is already queued in the right order is left alone, everything before it is moved
+/// to the top and everything after it to the bottom. Hashes that are not in `torrents` or
+/// not queued (a priority of 0 or -1) are skipped.
+///
+/// Only the relative order of `order` is guaranteed, other torrents may still be queued in
+/// between them.
+pub fn plan_queue_order(torrents: &[Torrent], order: &[Hash]) -> Vec<QueueMove> {
+    let queued = queued_positions(torrents, order);
+
+    // longest run that is already in queue order: [best_start, best_end)
+    let (mut best_start, mut best_end) = (0, 0);
+    let mut start = 0;
+    for end in 1..=queued.len() {
+        if end == queued.len() || queued[end].1 <= queued[end - 1].1 {
+            if end - start > best_end - best_start {
+                best_start = start;
+                best_end = end;
+            }
+            start = end;
+        }
+    }
+
+    // moving to the top in reverse leaves them in order above the run
+    let to_top = queued[..best_start]
+        .iter()
+        .rev()
+        .map(|(hash, _)| QueueMove::Top((*hash).clone()));
+    let to_bottom = queued[best_end..]
+        .iter()
+        .map(|(hash, _)| QueueMove::Bottom((*hash).clone()));
+
+    to_top.chain(to_bottom).collect()
+}
+
+/// The fewest top moves that put the queued torrents of `order` at the head of the queue, in
+/// that order
+///
+/// Hashes are skipped the same way as in `plan_queue_order`. The longest tail of `order` that
+/// already heads the queue in the right order is left alone.
+pub fn plan_queue_top(torrents: &[Torrent], order: &[Hash]) -> Vec<QueueMove> {
+    let queued = queued_positions(torrents, order);
+
+    // first entry of the tail that already sits at positions 1, 2, ...
+    let keep = (0..=queued.len())
+        .find(|&start| {
+            queued[start..]
+                .iter()
+                .enumerate()
+                .all(|(i, (_, priority))| *priority == i as i64 + 1)
+        })
+        .unwrap_or(queued.len());
+
+    queued[..keep]
+        .iter()
+        .rev()
+        .map(|(hash, _)| QueueMove::Top((*hash).clone()))
+        .collect()
+}
+
+/// The hashes of `order` that are queued, with their queue position
+fn queued_positions<'a>(torrents: &[Torrent], order: &'a [Hash]) -> Vec<(&'a Hash, i64)> {
+    order
+        .iter()
+        .filter_map(|hash| {
+            torrents
+                .iter()
+                .find(|torrent| torrent.hash() == hash)
+                .filter(|torrent| *torrent.priority() > 0)
+                .map(|torrent| (hash, *torrent.priority()))
+        })
+        .collect()
+}
+
+impl Api {
+    /// Reorder the queue so that the torrents of `order` are queued in that order
+    ///
+    /// See `plan_queue_order` for how the moves are chosen. Returns the moves that were made.
+    pub async fn reorder_queue(&self, order: &[Hash]) -> Result<Vec<QueueMove>, Error> {
+        let torrents = self.get_torrent_list().await?;
+        let moves = plan_queue_order(&torrents, order);
+        self.queue_moves(&moves).await?;
+
+        Ok(moves)
+    }
+
+    /// Move the torrents of `order` to the head of the queue, in that order
+    ///
+    /// See `plan_queue_top` for how the moves are chosen. Returns the moves that were made.
+    pub async fn promote_queue(&self, order: &[Hash]) -> Result<Vec<QueueMove>, Error> {
+        let torrents = self.get_torrent_list().await?;
+        let moves = plan_queue_top(&torrents, order);
+        self.queue_moves(&moves).await?;
+
+        Ok(moves)
+    }
+
+    async fn queue_moves(&self, moves: &[QueueMove]) -> Result<(), Error> {
+        for step in moves {
+            match step {
+                QueueMove::Top(hash) => hash.top_priority(self).await?,
+                QueueMove::Bottom(hash) => hash.bottom_priority(self).await?,
+            }
+        }
+
+        Ok(())
+    }
+}
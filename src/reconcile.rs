@@ -0,0 +1,243 @@
+//! Converging the categories and tags of a client to a desired state
+//!
+//! A `Spec` is usually loaded from a config file, e.g. with toml:
+//!
+//! ```toml
+//! tags = ["tv", "movies"]
+//!
+//! [categories.tv]
+//! save_path = "/data/tv"
+//!
+//! [categories.movies]
+//! save_path = "/data/movies"
+//! ```
+//!
+//! ```ignore
+//! let spec: Spec = toml::from_str(&config)?;
+//!
+//! let plan = api.plan_reconcile(&spec).await?;
+//! for action in plan.actions() {
+//!     println!("{:?}", action);
+//! }
+//! plan.apply(&api).await?;
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use derive_getters::Getters;
+use serde::Deserialize;
+
+use super::api::Api;
+use super::data::{Categories, CategoryDownloadPath};
+use super::error::Error;
+
+/// The categories and tags a client should have
+///
+/// A section left out of the spec is not managed, so it is never pruned.
+#[derive(Debug, Clone, Deserialize, Getters)]
+pub struct Spec {
+    #[serde(default)]
+    categories: Option<BTreeMap<String, CategorySpec>>,
+    #[serde(default)]
+    tags: Option<BTreeSet<String>>,
+    /// Remove the categories and tags that are not in their section of the spec. Defaults to true
+    #[serde(default = "prune_default")]
+    #[getter(skip)]
+    prune: bool,
+}
+
+fn prune_default() -> bool {
+    true
+}
+
+impl Default for Spec {
+    fn default() -> Self {
+        Self {
+            categories: None,
+            tags: None,
+            prune: prune_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Getters)]
+pub struct CategorySpec {
+    #[serde(alias = "savePath")]
+    save_path: String,
+}
+
+impl Spec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn category(mut self, name: &str, save_path: &str) -> Self {
+        let category = CategorySpec {
+            save_path: save_path.to_string(),
+        };
+        self.categories
+            .get_or_insert_with(BTreeMap::new)
+            .insert(name.to_string(), category);
+        self
+    }
+
+    pub fn tag(mut self, name: &str) -> Self {
+        self.tags
+            .get_or_insert_with(BTreeSet::new)
+            .insert(name.to_string());
+        self
+    }
+
+    /// Whether to remove the categories and tags that are not in their section of the spec
+    pub fn prune(mut self, prune: bool) -> Self {
+        self.prune = prune;
+        self
+    }
+
+    /// The actions that turn the live `categories` and `tags` of a client into this spec
+    pub fn plan(&self, categories: &BTreeMap<String, Categories>, tags: &BTreeSet<String>) -> Plan {
+        let mut actions = Vec::new();
+
+        if let Some(desired_categories) = &self.categories {
+            for (name, desired) in desired_categories {
+                match categories.get(name) {
+                    None => actions.push(Action::CreateCategory {
+                        name: name.clone(),
+                        save_path: desired.save_path.clone(),
+                    }),
+                    Some(live) if !same_path(live.save_path(), &desired.save_path) => {
+                        actions.push(Action::EditCategory {
+                            name: name.clone(),
+                            from: live.save_path().clone(),
+                            save_path: desired.save_path.clone(),
+                            download_path: live.download_path().clone(),
+                        })
+                    }
+                    Some(_) => (),
+                }
+            }
+
+            if self.prune {
+                for name in categories.keys() {
+                    // removing a parent category would also remove the desired subcategories
+                    let parent = format!("{}/", name);
+                    let is_parent = desired_categories.keys().any(|x| x.starts_with(&parent));
+
+                    if !desired_categories.contains_key(name) && !is_parent {
+                        actions.push(Action::RemoveCategory { name: name.clone() });
+                    }
+                }
+            }
+        }
+
+        if let Some(desired_tags) = &self.tags {
+            for tag in desired_tags.difference(tags) {
+                actions.push(Action::CreateTag { name: tag.clone() });
+            }
+
+            if self.prune {
+                for tag in tags.difference(desired_tags) {
+                    actions.push(Action::DeleteTag { name: tag.clone() });
+                }
+            }
+        }
+
+        Plan { actions }
+    }
+}
+
+/// qbittorrent may or may not report a trailing separator
+fn same_path(live: &str, desired: &str) -> bool {
+    live.trim_end_matches(['/', '\\']) == desired.trim_end_matches(['/', '\\'])
+}
+
+/// A single change made while reconciling
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    CreateCategory {
+        name: String,
+        save_path: String,
+    },
+    /// Change the save path, the download path is left as it was
+    EditCategory {
+        name: String,
+        from: String,
+        save_path: String,
+        download_path: CategoryDownloadPath,
+    },
+    RemoveCategory {
+        name: String,
+    },
+    CreateTag {
+        name: String,
+    },
+    /// Deleting a tag also removes it from every torrent
+    DeleteTag {
+        name: String,
+    },
+}
+
+/// The changes needed to reach a `Spec`, computed without changing anything
+#[derive(Debug, Clone, Default, Getters)]
+pub struct Plan {
+    actions: Vec<Action>,
+}
+
+impl Plan {
+    /// Whether the client already matches the spec
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Make every change of the plan, stopping at the first one that fails
+    ///
+    /// Planning and applying again after a failure picks up where this left off.
+    pub async fn apply(&self, api: &Api) -> Result<(), Error> {
+        let mut removed_categories = Vec::new();
+        let mut created_tags = Vec::new();
+        let mut deleted_tags = Vec::new();
+
+        for action in &self.actions {
+            match action {
+                Action::CreateCategory { name, save_path } => {
+                    api.add_category(name, save_path).await?
+                }
+                Action::EditCategory {
+                    name,
+                    save_path,
+                    download_path,
+                    ..
+                } => api.edit_category(name, save_path, download_path).await?,
+                Action::RemoveCategory { name } => removed_categories.push(name.clone()),
+                Action::CreateTag { name } => created_tags.push(name.clone()),
+                Action::DeleteTag { name } => deleted_tags.push(name.clone()),
+            }
+        }
+
+        // the other actions can be sent in a single request each
+        api.remove_categories(&removed_categories).await?;
+        api.create_tags(&created_tags).await?;
+        api.delete_tags(&deleted_tags).await?;
+
+        Ok(())
+    }
+}
+
+impl Api {
+    /// Compare the categories and tags of the client to `spec`, without changing anything
+    pub async fn plan_reconcile(&self, spec: &Spec) -> Result<Plan, Error> {
+        let categories = self.get_all_categories().await?;
+        let tags = self.get_all_tags().await?.into_iter().collect();
+
+        Ok(spec.plan(&categories, &tags))
+    }
+
+    /// Plan and apply the changes that make the client match `spec`
+    ///
+    /// Returns the plan that was applied, it is empty if nothing had to change.
+    pub async fn reconcile(&self, spec: &Spec) -> Result<Plan, Error> {
+        let plan = self.plan_reconcile(spec).await?;
+        plan.apply(self).await?;
+        Ok(plan)
+    }
+}
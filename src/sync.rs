@@ -0,0 +1,231 @@
+//! Incremental state tracking on top of the `sync/*` endpoints
+//!
+//! qbittorrent answers `sync/maindata` with only what changed since the `rid` of the
+//! previous response. `MainDataSync` (and `PeersSync` for `sync/torrentPeers`) keeps the
+//! last `rid` along with a local snapshot and merges each partial response into it, so
+//! polling does not require refetching every torrent.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{Map, Value};
+
+use super::api::Api;
+use super::data::{Categories, Hash, MainData, Peer, ServerState, Torrent, TorrentPeers};
+use super::error::Error;
+
+/// Local snapshot of `sync/maindata`, kept up to date with `Api::sync`
+///
+/// ```ignore
+/// let mut state = MainDataSync::new();
+/// loop {
+///     api.sync(&mut state).await?;
+///     println!("tracking {} torrents", state.torrents().len());
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MainDataSync {
+    rid: u64,
+    raw_torrents: BTreeMap<String, Map<String, Value>>,
+    torrents: BTreeMap<String, Torrent>,
+    raw_categories: BTreeMap<String, Map<String, Value>>,
+    categories: BTreeMap<String, Categories>,
+    tags: BTreeSet<String>,
+    queueing: Option<bool>,
+    raw_server_state: Map<String, Value>,
+    server_state: ServerState,
+}
+
+impl MainDataSync {
+    /// An empty snapshot. The first sync will request a full update
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Response id of the last update applied
+    pub fn rid(&self) -> u64 {
+        self.rid
+    }
+
+    /// All known torrents, keyed by their hash
+    pub fn torrents(&self) -> &BTreeMap<String, Torrent> {
+        &self.torrents
+    }
+
+    /// All known categories, keyed by their name
+    pub fn categories(&self) -> &BTreeMap<String, Categories> {
+        &self.categories
+    }
+
+    pub fn tags(&self) -> &BTreeSet<String> {
+        &self.tags
+    }
+
+    pub fn queueing(&self) -> Option<bool> {
+        self.queueing
+    }
+
+    pub fn server_state(&self) -> &ServerState {
+        &self.server_state
+    }
+
+    /// Merge a (possibly partial) `sync/maindata` response into this snapshot
+    ///
+    /// Only torrents and categories present in `update` are re-parsed, everything else is
+    /// left as it was.
+    pub fn apply(&mut self, update: MainData) -> Result<(), Error> {
+        if *update.full_update() {
+            self.raw_torrents.clear();
+            self.torrents.clear();
+            self.raw_categories.clear();
+            self.categories.clear();
+            self.tags.clear();
+            self.raw_server_state.clear();
+        }
+
+        for (hash, partial) in update.torrents() {
+            let raw = self.raw_torrents.entry(hash.clone()).or_default();
+            merge(raw, partial);
+
+            // the hash is the key of the map, it is not repeated inside of the object
+            let mut full = raw.clone();
+            full.insert("hash".into(), Value::String(hash.clone()));
+            let torrent = serde_json::from_value(Value::Object(full))?;
+            self.torrents.insert(hash.clone(), torrent);
+        }
+        for hash in update.torrents_removed() {
+            self.raw_torrents.remove(hash);
+            self.torrents.remove(hash);
+        }
+
+        for (name, partial) in update.categories() {
+            let raw = self.raw_categories.entry(name.clone()).or_default();
+            merge(raw, partial);
+
+            let category = serde_json::from_value(Value::Object(raw.clone()))?;
+            self.categories.insert(name.clone(), category);
+        }
+        for name in update.categories_removed() {
+            self.raw_categories.remove(name);
+            self.categories.remove(name);
+        }
+
+        self.tags.extend(update.tags().iter().cloned());
+        for tag in update.tags_removed() {
+            self.tags.remove(tag);
+        }
+
+        if let Some(queueing) = update.queueing() {
+            self.queueing = Some(*queueing);
+        }
+
+        if let Some(partial) = update.server_state() {
+            merge(&mut self.raw_server_state, partial);
+            self.server_state =
+                serde_json::from_value(Value::Object(self.raw_server_state.clone()))?;
+        }
+
+        self.rid = *update.rid();
+        Ok(())
+    }
+}
+
+/// Local snapshot of the peers connected to a single torrent, kept up to date with
+/// `traits::Peers::sync_peers`
+#[derive(Debug, Default, Clone)]
+pub struct PeersSync {
+    hash: Option<Hash>,
+    rid: u64,
+    raw_peers: BTreeMap<String, Map<String, Value>>,
+    peers: BTreeMap<String, Peer>,
+    show_flags: Option<bool>,
+}
+
+impl PeersSync {
+    /// An empty snapshot. The first sync will request a full update
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Response id of the last update applied
+    pub fn rid(&self) -> u64 {
+        self.rid
+    }
+
+    /// The torrent this snapshot was last synced for
+    pub fn hash(&self) -> Option<&Hash> {
+        self.hash.as_ref()
+    }
+
+    /// All connected peers, keyed by `ip:port`
+    pub fn peers(&self) -> &BTreeMap<String, Peer> {
+        &self.peers
+    }
+
+    pub fn show_flags(&self) -> Option<bool> {
+        self.show_flags
+    }
+
+    /// Response id to request the next update with. Syncing a different torrent than last
+    /// time starts over from a full update.
+    pub(crate) fn rid_for(&self, hash: &Hash) -> u64 {
+        match &self.hash {
+            Some(last) if last.hash == hash.hash => self.rid,
+            _ => 0,
+        }
+    }
+
+    /// Merge a (possibly partial) `sync/torrentPeers` response for `hash` into this snapshot
+    pub fn apply(&mut self, hash: &Hash, update: TorrentPeers) -> Result<(), Error> {
+        if *update.full_update() || self.rid_for(hash) == 0 {
+            self.raw_peers.clear();
+            self.peers.clear();
+        }
+
+        for (address, partial) in update.peers() {
+            let raw = self.raw_peers.entry(address.clone()).or_default();
+            merge(raw, partial);
+
+            let peer = serde_json::from_value(Value::Object(raw.clone()))?;
+            self.peers.insert(address.clone(), peer);
+        }
+        for address in update.peers_removed() {
+            self.raw_peers.remove(address);
+            self.peers.remove(address);
+        }
+
+        if let Some(show_flags) = update.show_flags() {
+            self.show_flags = Some(*show_flags);
+        }
+
+        self.hash = Some(hash.clone());
+        self.rid = *update.rid();
+        Ok(())
+    }
+}
+
+/// Overwrite the keys of `target` with the (changed) keys in `partial`
+fn merge(target: &mut Map<String, Value>, partial: &Map<String, Value>) {
+    for (key, value) in partial {
+        target.insert(key.clone(), value.clone());
+    }
+}
+
+impl Api {
+    /// Fetch the raw changes to the client state since the response id `rid`
+    ///
+    /// A `rid` of 0 requests a full update.
+    pub async fn get_main_data(&self, rid: u64) -> Result<MainData, Error> {
+        let addr = push_own! {self.address, "/api/v2/sync/maindata?rid=", &rid.to_string()};
+
+        let res = self.send(|client| client.get(&addr)).await?.bytes().await?;
+
+        let data = serde_json::from_slice(&res)?;
+        Ok(data)
+    }
+
+    /// Fetch everything that changed since the last sync of `state` and merge it in
+    pub async fn sync(&self, state: &mut MainDataSync) -> Result<(), Error> {
+        let data = self.get_main_data(state.rid()).await?;
+        state.apply(data)
+    }
+}
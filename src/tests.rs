@@ -1,9 +1,12 @@
-use super::api::Api;
+use super::api::{self, Api};
 use super::data;
 use super::error::Error;
+use super::migration;
 use super::queries;
+use super::queue;
+use super::reconcile;
+use super::sync;
 use super::traits::*;
-use tokio;
 
 fn _is_send<T: Send>(_: T) {}
 fn _is_sync<T: Sync>(_: T) {}
@@ -35,6 +38,67 @@ async fn default_api() -> Result<Api, Error> {
     Api::new("admin", "adminadmin", "http://localhost:8080").await
 }
 
+#[tokio::test]
+async fn login_wrong_password() {
+    let api = Api::new("admin", "not the password", "http://localhost:8080").await;
+    match api {
+        Err(Error::LoginFailed) => {}
+        other => panic! {"expected a failed login, got {:?}", other},
+    }
+}
+
+#[tokio::test]
+async fn builder_sub_path() {
+    // a trailing slash must not end up doubled in front of /api/v2
+    let api = Api::builder("http://localhost:8080/")
+        .credentials("admin", "adminadmin")
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(api.address, "http://localhost:8080");
+    api.application_version().await.unwrap();
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    // requires "Bypass authentication for clients on localhost"
+    let api = Api::new_unauthenticated("http://localhost:8080")
+        .await
+        .unwrap();
+    assert! {!api.make_headers().unwrap().contains_key("cookie")};
+    api.get_torrent_list().await.unwrap();
+}
+
+#[tokio::test]
+async fn logout() {
+    let api = default_api().await.unwrap();
+    assert! {api.is_session_valid().await.unwrap()};
+    api.logout().await.unwrap();
+}
+
+#[tokio::test]
+async fn reauthenticate_after_logout() {
+    let api = default_api().await.unwrap();
+
+    // end the session behind the back of `api`
+    let cookie = api.cookie.read().unwrap().clone();
+    let other = Api {
+        cookie: std::sync::RwLock::new(cookie),
+        address: api.address.clone(),
+        client: api.client.clone(),
+        credentials: None,
+        headers: api.headers.clone(),
+        timeout: api.timeout,
+    };
+    other.logout().await.unwrap();
+    assert! {!api.is_session_valid().await.unwrap()};
+
+    // the next request logs in again
+    api.get_torrent_list().await.unwrap();
+    assert! {api.is_session_valid().await.unwrap()};
+}
+
 #[allow(dead_code)]
 async fn get_first_torrent() -> (Api, data::Torrent) {
     let api = default_api().await.expect("could not start api");
@@ -44,7 +108,7 @@ async fn get_first_torrent() -> (Api, data::Torrent) {
         .await
         .expect("could not get torrnet list");
 
-    let torrent = if let Some(_) = torrent_list.get(0) {
+    let torrent = if !torrent_list.is_empty() {
         torrent_list.remove(0)
     } else {
         panic! {"there were no items in the torrent list to check"}
@@ -85,6 +149,73 @@ async fn add_new_torrent() {
     assert! {add_torrent_result.is_ok()};
 }
 
+/// A single file torrent of one byte, with a placeholder piece hash
+#[allow(dead_code)]
+fn torrent_file_bytes(name: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"d4:infod6:lengthi1e4:name");
+    bytes.extend_from_slice(format!("{}:{}", name.len(), name).as_bytes());
+    bytes.extend_from_slice(b"12:piece lengthi16384e6:pieces20:");
+    bytes.extend_from_slice(&[0; 20]);
+    bytes.extend_from_slice(b"ee");
+    bytes
+}
+
+#[tokio::test]
+async fn add_torrent_files() {
+    let api = default_api().await.unwrap();
+
+    let path = std::env::temp_dir().join("qbittorrent_upload_test.torrent");
+    std::fs::write(&path, torrent_file_bytes("upload test b")).unwrap();
+
+    let download = queries::TorrentDownloadBuilder::default()
+        .torrent_file(queries::TorrentFile::from_bytes(
+            "a.torrent",
+            torrent_file_bytes("upload test a"),
+        ))
+        .torrent_file(queries::TorrentFile::from_path(&path))
+        .category("add_new_torrent_test_category")
+        .paused("true")
+        .build()
+        .unwrap();
+
+    api.add_new_torrent(&download).await.unwrap();
+}
+
+#[test]
+fn torrent_file_sources() {
+    let path = std::env::temp_dir().join("qbittorrent_load_test.torrent");
+    std::fs::write(&path, b"d4:infodee").unwrap();
+
+    let (name, data) = queries::TorrentFile::from_path(&path).load().unwrap();
+    assert_eq!(name, "qbittorrent_load_test.torrent");
+    assert_eq!(data, b"d4:infodee");
+
+    let (name, data) = queries::TorrentFile::from_bytes("x.torrent", b"de".to_vec())
+        .load()
+        .unwrap();
+    assert_eq!((name.as_str(), data.as_slice()), ("x.torrent", &b"de"[..]));
+
+    let missing = queries::TorrentFile::from_path("/does/not/exist.torrent").load();
+    assert!(matches!(missing, Err(Error::Io(_))));
+
+    // the files are not sent with the other (text) fields
+    let download = queries::TorrentDownloadBuilder::default()
+        .torrents(vec![queries::TorrentFile::from_path(&path)])
+        .paused("true")
+        .build()
+        .unwrap();
+    assert_eq!(download.torrent_files().len(), 1);
+    assert_eq!(
+        serde_json::to_value(&download).unwrap()["paused"],
+        serde_json::json!("true")
+    );
+    assert!(serde_json::to_value(&download)
+        .unwrap()
+        .get("torrents")
+        .is_none());
+}
+
 #[tokio::test]
 async fn properties() {
     let (api, torrent) = get_first_torrent().await;
@@ -103,6 +234,51 @@ async fn trackers() {
     trackers.expect("could not get torrent trackers");
 }
 
+#[tokio::test]
+async fn edit_trackers() {
+    let (api, torrent) = get_first_torrent().await;
+    let url = "http://tracker.example.com:6969/announce".to_string();
+    let new_url = "http://tracker.example.org:6969/announce".to_string();
+
+    torrent
+        .add_trackers(&api, std::slice::from_ref(&url))
+        .await
+        .unwrap();
+    torrent.edit_tracker(&api, &url, &new_url).await.unwrap();
+
+    let trackers = torrent.trackers(&api).await.unwrap();
+    assert! {trackers.iter().any(|x| x.url() == &new_url)};
+
+    torrent.remove_trackers(&api, &[new_url]).await.unwrap();
+
+    // editing a tracker that does not exist is a conflict
+    match torrent
+        .edit_tracker(&api, &url, "http://a.b/announce")
+        .await
+    {
+        Err(Error::Conflict(_)) => {}
+        other => panic! {"expected a conflict, got {:?}", other},
+    }
+}
+
+#[tokio::test]
+async fn web_seeds() {
+    let (api, torrent) = get_first_torrent().await;
+    let url = "http://mirror.example.com/files/".to_string();
+    let new_url = "http://mirror.example.org/files/".to_string();
+
+    torrent
+        .add_web_seeds(&api, std::slice::from_ref(&url))
+        .await
+        .unwrap();
+    torrent.edit_web_seed(&api, &url, &new_url).await.unwrap();
+
+    let seeds = torrent.web_seeds(&api).await.unwrap();
+    assert! {seeds.iter().any(|x| x.url() == &new_url)};
+
+    torrent.remove_web_seeds(&api, &[new_url]).await.unwrap();
+}
+
 #[tokio::test]
 async fn contents() {
     let api = default_api().await.unwrap();
@@ -124,6 +300,97 @@ async fn contents() {
     contents.unwrap();
 }
 
+#[tokio::test]
+async fn file_priority() {
+    let (api, torrent) = get_first_torrent().await;
+
+    let contents = torrent.contents(&api).await.unwrap();
+    let file = &contents[0];
+    let original = *file.priority();
+
+    file.set_priority(&api, data::FilePriority::High)
+        .await
+        .unwrap();
+    torrent
+        .set_file_priority(&api, &[*file.index()], original)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn file_contents_index_and_priority() {
+    let files: Vec<data::TorrentInfoSerde> = serde_json::from_value(serde_json::json!([
+        {"name": "pack/e01.mkv", "size": 100, "progress": 1.0, "priority": 0,
+         "is_seed": false, "piece_range": [0, 3], "availability": 1.0},
+        {"index": 5, "name": "pack/e02.mkv", "size": 100, "progress": 0.5, "priority": 7,
+         "piece_range": [3, 6], "availability": 0.5}
+    ]))
+    .unwrap();
+
+    let hash = data::Hash::from("abc".to_string());
+    let files: Vec<_> = files
+        .into_iter()
+        .enumerate()
+        .map(|(position, x)| x.into_info(&hash, position))
+        .collect();
+
+    assert_eq!(*files[0].index(), 0);
+    assert_eq!(*files[0].priority(), data::FilePriority::DoNotDownload);
+    assert_eq!(*files[1].index(), 5);
+    assert_eq!(*files[1].priority(), data::FilePriority::Maximal);
+
+    // priorities set outside of the web UI are kept as they are
+    let other: data::FilePriority = serde_json::from_value(serde_json::json!(4)).unwrap();
+    assert_eq!(other, data::FilePriority::Other(4));
+    assert_eq!(serde_json::to_value(other).unwrap(), serde_json::json!(4));
+}
+
+#[tokio::test]
+async fn piece_states() {
+    let (api, torrent) = get_first_torrent().await;
+
+    let states = torrent.piece_states(&api).await.unwrap();
+    let hashes = torrent.piece_hashes(&api).await.unwrap();
+    assert_eq!(states.len(), hashes.len());
+
+    for file in torrent.contents(&api).await.unwrap() {
+        dbg! {file.name(), file.piece_completion(&states)};
+    }
+}
+
+#[test]
+fn file_piece_completion() {
+    let files: Vec<data::TorrentInfoSerde> = serde_json::from_value(serde_json::json!([
+        {"name": "a", "size": 10, "progress": 1.0, "priority": 1, "piece_range": [0, 2],
+         "availability": 1.0},
+        {"name": "b", "size": 10, "progress": 0.2, "priority": 1, "piece_range": [2, 5],
+         "availability": 1.0},
+        {"name": "empty", "size": 0, "progress": 1.0, "priority": 1, "piece_range": [5, 4],
+         "availability": 1.0}
+    ]))
+    .unwrap();
+    let states: Vec<data::PieceState> =
+        serde_json::from_value(serde_json::json!([2, 2, 2, 1, 0, 0])).unwrap();
+
+    let hash = data::Hash::from("abc".to_string());
+    let files: Vec<_> = files
+        .into_iter()
+        .enumerate()
+        .map(|(position, x)| x.into_info(&hash, position))
+        .collect();
+
+    let a = files[0].piece_completion(&states);
+    assert!(a.is_complete());
+    assert_eq!(*a.total(), 3);
+
+    let b = files[1].piece_completion(&states);
+    assert!(!b.is_complete());
+    assert_eq!((*b.total(), *b.downloaded(), *b.downloading()), (4, 1, 1));
+    assert_eq!(b.missing(), 2);
+
+    assert_eq!(*files[2].piece_completion(&states).total(), 0);
+}
+
 #[tokio::test]
 async fn test_pause() {
     let api = default_api().await.unwrap();
@@ -164,7 +431,7 @@ async fn test_pause() {
 
     // since new_torrent is a vector of all matches, pop off the only one inside it
     let first = new_torrent.unwrap();
-    let first = first.get(0).unwrap();
+    let first = first.first().unwrap();
 
     // check that it did indeep pause
     match first.state() {
@@ -180,6 +447,21 @@ async fn test_pause() {
         .expect("did not resume torrent");
 }
 
+#[tokio::test]
+async fn delete() {
+    let api = default_api().await.unwrap();
+    let torrents = queries::TorrentRequestBuilder::default()
+        .category("add_new_torrent_test_category")
+        .build()
+        .unwrap()
+        .send(&api)
+        .await
+        .unwrap();
+
+    let hashes: Vec<data::Hash> = torrents.iter().map(|x| x.hash().clone()).collect();
+    hashes.delete(&api, true).await.unwrap();
+}
+
 #[tokio::test]
 async fn set_category() {
     let (api, torrent) = get_first_torrent().await;
@@ -198,7 +480,67 @@ async fn add_category() {
     let api = default_api().await.unwrap();
     let response = api.add_category("ADD_CATEGORY", "E:\\testpath").await;
     dbg! {&response};
-    response.unwrap();
+
+    match response {
+        // left over from an earlier run
+        Err(Error::Conflict(_)) => {}
+        other => other.unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn category_lifecycle() {
+    let api = default_api().await.unwrap();
+    let name = "category_lifecycle_test";
+    let download_path = data::CategoryDownloadPath::Path("/tmp/incomplete".into());
+
+    api.create_category(name, "/tmp/complete", &data::CategoryDownloadPath::Disabled)
+        .await
+        .unwrap();
+    match api.add_category(name, "/tmp/complete").await {
+        Err(Error::Conflict(_)) => {}
+        other => panic! {"expected a conflict, got {:?}", other},
+    }
+
+    api.edit_category(name, "/tmp/moved", &download_path)
+        .await
+        .unwrap();
+    let categories = api.get_all_categories().await.unwrap();
+    assert_eq!(categories[name].save_path(), "/tmp/moved");
+    assert_eq!(categories[name].download_path(), &download_path);
+
+    api.remove_categories(&[name.to_string()]).await.unwrap();
+    assert!(!api.get_all_categories().await.unwrap().contains_key(name));
+}
+
+#[test]
+fn category_download_paths() {
+    let categories: std::collections::BTreeMap<String, data::Categories> =
+        serde_json::from_value(serde_json::json!({
+            "old": {"name": "old", "savePath": "/a"},
+            "disabled": {"name": "disabled", "savePath": "/b", "download_path": false},
+            "custom": {"name": "custom", "savePath": "/c", "download_path": "/tmp"}
+        }))
+        .unwrap();
+
+    assert_eq!(
+        categories["old"].download_path(),
+        &data::CategoryDownloadPath::Default
+    );
+    assert_eq!(
+        categories["disabled"].download_path(),
+        &data::CategoryDownloadPath::Disabled
+    );
+    assert_eq!(
+        categories["custom"].download_path(),
+        &data::CategoryDownloadPath::Path("/tmp".into())
+    );
+
+    assert!(api::is_valid_category("tv/hd"));
+    assert!(!api::is_valid_category(""));
+    assert!(!api::is_valid_category("/tv"));
+    assert!(!api::is_valid_category("tv//hd"));
+    assert!(!api::is_valid_category("tv\\hd"));
 }
 
 #[tokio::test]
@@ -224,3 +566,759 @@ async fn get_alternate_speed_limits_state() {
     dbg! {&cats};
     cats.unwrap();
 }
+
+#[tokio::test]
+async fn global_speed_limits() {
+    let api = default_api().await.unwrap();
+    let original = api.get_global_download_limit().await.unwrap();
+
+    let limit = data::SpeedLimit::mebibytes_per_second(10);
+    api.set_global_download_limit(limit).await.unwrap();
+    assert_eq!(api.get_global_download_limit().await.unwrap(), limit);
+
+    api.set_global_download_limit(original).await.unwrap();
+    api.get_global_upload_limit().await.unwrap();
+}
+
+#[tokio::test]
+async fn torrent_speed_limits() {
+    let (api, torrent) = get_first_torrent().await;
+    let limit = data::SpeedLimit::kibibytes_per_second(512);
+
+    torrent.set_upload_limit(&api, limit).await.unwrap();
+    let limits = torrent.upload_limit(&api).await.unwrap();
+    assert_eq!(limits.get(&**torrent.hash()), Some(&limit));
+
+    torrent
+        .set_upload_limit(&api, data::SpeedLimit::Unlimited)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn speed_limit_units() {
+    let limits: std::collections::BTreeMap<String, data::SpeedLimit> =
+        serde_json::from_value(serde_json::json!({"a": 1048576, "b": 0, "c": -1})).unwrap();
+
+    assert_eq!(limits["a"], data::SpeedLimit::mebibytes_per_second(1));
+    assert_eq!(limits["a"].bytes_per_second(), Some(1024 * 1024));
+    assert_eq!(limits["b"], data::SpeedLimit::Unlimited);
+    assert_eq!(limits["c"].bytes_per_second(), None);
+
+    assert_eq!(i64::from(data::SpeedLimit::Unlimited), 0);
+    assert_eq!(i64::from(data::SpeedLimit::kibibytes_per_second(2)), 2048);
+
+    // 0 bytes/s is unlimited, however it was made
+    assert_eq!(
+        data::SpeedLimit::BytesPerSecond(0),
+        data::SpeedLimit::Unlimited
+    );
+    assert!(matches!(
+        data::SpeedLimit::kibibytes_per_second(0),
+        data::SpeedLimit::Unlimited
+    ));
+
+    // huge limits saturate instead of wrapping around
+    let huge = data::SpeedLimit::mebibytes_per_second(u64::MAX);
+    assert_eq!(huge.bytes_per_second(), Some(u64::MAX));
+    assert_eq!(i64::from(huge), i64::MAX);
+}
+
+#[test]
+fn share_limits_form() {
+    let limits = queries::ShareLimitsRequestBuilder::default()
+        .ratio(queries::ShareLimit::Limit(1.5))
+        .seeding_time(queries::ShareLimit::Limit(std::time::Duration::from_secs(
+            2 * 60 * 60,
+        )))
+        .inactive_seeding_time(queries::ShareLimit::Unlimited)
+        .build()
+        .unwrap();
+
+    let form = serde_urlencoded::to_string(limits.form("abc")).unwrap();
+    assert_eq!(
+        form,
+        "hashes=abc&ratioLimit=1.5&seedingTimeLimit=120&inactiveSeedingTimeLimit=-1"
+    );
+
+    let form = serde_urlencoded::to_string(queries::ShareLimitsRequest::default().form("abc"));
+    assert_eq!(
+        form.unwrap(),
+        "hashes=abc&ratioLimit=-2.0&seedingTimeLimit=-2&inactiveSeedingTimeLimit=-2"
+    );
+
+    // partial minutes round up instead of down to 0
+    let limits = queries::ShareLimitsRequestBuilder::default()
+        .seeding_time(queries::ShareLimit::Limit(std::time::Duration::from_secs(
+            30,
+        )))
+        .inactive_seeding_time(queries::ShareLimit::Limit(std::time::Duration::from_secs(
+            61,
+        )))
+        .build()
+        .unwrap();
+    let form = serde_urlencoded::to_string(limits.form("abc")).unwrap();
+    assert_eq!(
+        form,
+        "hashes=abc&ratioLimit=-2.0&seedingTimeLimit=1&inactiveSeedingTimeLimit=2"
+    );
+}
+
+#[tokio::test]
+async fn share_limits() {
+    let (api, torrent) = get_first_torrent().await;
+
+    let limits = queries::ShareLimitsRequestBuilder::default()
+        .ratio(queries::ShareLimit::Limit(2.0))
+        .build()
+        .unwrap();
+    torrent.set_share_limits(&api, &limits).await.unwrap();
+
+    torrent
+        .set_share_limits(&api, &queries::ShareLimitsRequest::default())
+        .await
+        .unwrap();
+}
+
+#[allow(dead_code)]
+fn torrent_json(name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "added_on": 1, "amount_left": 0, "auto_tmm": false, "category": "", "completed": 0,
+        "completion_on": 0, "dl_limit": -1, "dlspeed": 0, "downloaded": 0,
+        "downloaded_session": 0, "eta": 0, "f_l_piece_prio": false, "force_start": false,
+        "last_activity": 0, "magnet_uri": "", "max_ratio": -1.0, "max_seeding_time": -1,
+        "name": name, "num_complete": 0, "num_incomplete": 0, "num_leechs": 0, "num_seeds": 0,
+        "priority": 0, "progress": 0.0, "ratio": 0.0, "ratio_limit": -2.0, "save_path": "/",
+        "seeding_time_limit": -2, "seen_complete": 0, "seq_dl": false, "size": 0,
+        "state": "pausedDL", "super_seeding": false, "tags": "", "time_active": 0,
+        "total_size": 0, "tracker": "", "up_limit": -1, "uploaded": 0, "uploaded_session": 0,
+        "upspeed": 0
+    })
+}
+
+/// `torrent_json` with a hash, as needed to parse it into a `data::Torrent`
+fn torrent_with_hash(hash: &str) -> serde_json::Value {
+    let mut torrent = torrent_json(hash);
+    torrent["hash"] = serde_json::json!(hash);
+    torrent
+}
+
+#[test]
+fn main_data_sync_merges_partial_updates() {
+    let mut state = sync::MainDataSync::new();
+
+    let full: data::MainData = serde_json::from_value(serde_json::json!({
+        "rid": 1,
+        "full_update": true,
+        "torrents": {"aaaa": torrent_json("first"), "bbbb": torrent_json("second")},
+        "categories": {"movies": {"name": "movies", "savePath": "/movies"}},
+        "tags": ["one", "two"],
+        "server_state": {}
+    }))
+    .unwrap();
+    state.apply(full).unwrap();
+    assert_eq!(state.rid(), 1);
+    assert_eq!(state.torrents().len(), 2);
+    assert_eq!(state.torrents()["aaaa"].hash().as_str(), "aaaa");
+
+    let partial: data::MainData = serde_json::from_value(serde_json::json!({
+        "rid": 2,
+        "torrents": {"aaaa": {"name": "renamed"}},
+        "torrents_removed": ["bbbb"],
+        "categories_removed": ["movies"],
+        "tags_removed": ["one"]
+    }))
+    .unwrap();
+    state.apply(partial).unwrap();
+    assert_eq!(state.rid(), 2);
+    assert_eq!(state.torrents().len(), 1);
+    assert_eq!(state.torrents()["aaaa"].name(), "renamed");
+    assert!(state.categories().is_empty());
+    assert_eq!(state.tags().iter().collect::<Vec<_>>(), vec!["two"]);
+}
+
+#[test]
+fn server_state_partial_updates() {
+    let mut state = sync::MainDataSync::new();
+
+    let full: data::MainData = serde_json::from_value(serde_json::json!({
+        "rid": 1,
+        "full_update": true,
+        "server_state": {
+            "alltime_dl": 100, "connection_status": "connected", "free_space_on_disk": 5000,
+            "global_ratio": "1.50", "read_cache_hits": "0", "use_alt_speed_limits": false
+        }
+    }))
+    .unwrap();
+    state.apply(full).unwrap();
+
+    let partial: data::MainData = serde_json::from_value(serde_json::json!({
+        "rid": 2,
+        "server_state": {"free_space_on_disk": 4000, "use_alt_speed_limits": true}
+    }))
+    .unwrap();
+    state.apply(partial).unwrap();
+
+    let server = state.server_state();
+    assert_eq!(*server.alltime_dl(), 100);
+    assert_eq!(*server.free_space_on_disk(), 4000);
+    assert_eq!(*server.global_ratio(), 1.5);
+    assert_eq!(
+        *server.connection_status(),
+        data::ConnectionStatus::Connected
+    );
+    assert!(*server.use_alt_speed_limits());
+}
+
+#[test]
+fn peers_sync_merges_partial_updates() {
+    let hash = data::Hash::from("aaaa".to_string());
+    let mut state = sync::PeersSync::new();
+    assert_eq!(state.rid_for(&hash), 0);
+
+    let full: data::TorrentPeers = serde_json::from_value(serde_json::json!({
+        "rid": 1,
+        "full_update": true,
+        "show_flags": true,
+        "peers": {
+            "1.2.3.4:5000": {"ip": "1.2.3.4", "port": 5000, "client": "qBittorrent", "progress": 0.5},
+            "5.6.7.8:6000": {"ip": "5.6.7.8", "port": 6000, "client": "Transmission"}
+        }
+    }))
+    .unwrap();
+    state.apply(&hash, full).unwrap();
+    assert_eq!(state.rid_for(&hash), 1);
+    assert_eq!(state.peers().len(), 2);
+
+    let partial: data::TorrentPeers = serde_json::from_value(serde_json::json!({
+        "rid": 2,
+        "peers": {"1.2.3.4:5000": {"progress": 0.75, "dl_speed": 1000}},
+        "peers_removed": ["5.6.7.8:6000"]
+    }))
+    .unwrap();
+    state.apply(&hash, partial).unwrap();
+
+    let peer = &state.peers()["1.2.3.4:5000"];
+    assert_eq!(state.peers().len(), 1);
+    assert_eq!(peer.client(), "qBittorrent");
+    assert_eq!(*peer.progress(), 0.75);
+    assert_eq!(*peer.dl_speed(), 1000);
+
+    // a different torrent starts over from a full update
+    let other = data::Hash::from("bbbb".to_string());
+    assert_eq!(state.rid_for(&other), 0);
+}
+
+#[tokio::test]
+async fn sync_peers() {
+    let (api, torrent) = get_first_torrent().await;
+    let mut state = sync::PeersSync::new();
+    torrent.sync_peers(&api, &mut state).await.unwrap();
+    dbg! {state.peers()};
+}
+
+#[tokio::test]
+async fn sync_main_data() {
+    let api = default_api().await.unwrap();
+    let mut state = sync::MainDataSync::new();
+    api.sync(&mut state).await.unwrap();
+    let rid = state.rid();
+    api.sync(&mut state).await.unwrap();
+    dbg! {state.torrents().len()};
+    assert! {state.rid() > rid};
+}
+
+#[test]
+fn preferences_builder_only_serializes_changes() {
+    let update = data::PreferencesBuilder::default()
+        .dl_limit(1000)
+        .scheduler_days(data::SchedulerDays::Weekends)
+        .proxy_type(data::ProxyType::Socks5)
+        .build()
+        .unwrap();
+
+    let json = serde_json::to_value(&update).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"dl_limit": 1000, "scheduler_days": 2, "proxy_type": "SOCKS5"})
+    );
+}
+
+#[test]
+fn preferences_accept_legacy_codes() {
+    let prefs: data::Preferences = serde_json::from_value(serde_json::json!({
+        "proxy_type": 4,
+        "scan_dirs": {"/watch": 0, "/other": "/downloads"},
+        "max_ratio_act": 1,
+        "banned_IPs": "1.2.3.4"
+    }))
+    .unwrap();
+
+    assert_eq!(*prefs.proxy_type(), Some(data::ProxyType::Socks5WithAuth));
+    assert_eq!(*prefs.max_ratio_act(), Some(data::MaxRatioAction::Remove));
+    assert_eq!(prefs.banned_ips().as_deref(), Some("1.2.3.4"));
+    let scan_dirs = prefs.scan_dirs().as_ref().unwrap();
+    assert_eq!(scan_dirs["/watch"], data::ScanDirTarget::MonitoredFolder);
+    assert_eq!(
+        scan_dirs["/other"],
+        data::ScanDirTarget::Path("/downloads".into())
+    );
+}
+
+#[test]
+fn proxy_type_round_trip() {
+    for proxy in [
+        data::ProxyType::None,
+        data::ProxyType::Http,
+        data::ProxyType::Socks5,
+        data::ProxyType::Socks4,
+        data::ProxyType::HttpWithAuth,
+        data::ProxyType::Socks5WithAuth,
+    ] {
+        let json = serde_json::to_value(proxy).unwrap();
+        let parsed: data::ProxyType = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, proxy);
+    }
+}
+
+#[test]
+fn preferences_send_the_proxy_type_the_server_understands() {
+    let legacy: data::Preferences =
+        serde_json::from_value(serde_json::json!({"proxy_type": 1})).unwrap();
+    assert_eq!(legacy.to_json(true).unwrap()["proxy_type"], 1);
+    assert_eq!(legacy.to_json(false).unwrap()["proxy_type"], "HTTP");
+
+    let authenticated: data::Preferences =
+        serde_json::from_value(serde_json::json!({"proxy_type": 4})).unwrap();
+    let json = authenticated.to_json(false).unwrap();
+    assert_eq!(json["proxy_type"], "SOCKS5");
+    assert_eq!(json["proxy_auth_enabled"], true);
+    assert_eq!(authenticated.to_json(true).unwrap()["proxy_type"], 4);
+
+    // 4.6 and later keep authentication separate from the proxy type
+    let current: data::Preferences = serde_json::from_value(serde_json::json!({
+        "proxy_type": "HTTP",
+        "proxy_auth_enabled": true
+    }))
+    .unwrap();
+    assert_eq!(current.to_json(true).unwrap()["proxy_type"], 3);
+
+    assert!(api::api_older_than("2.8.19", &[2, 9, 2]));
+    assert!(!api::api_older_than("2.9.2", &[2, 9, 2]));
+    assert!(!api::api_older_than("2.10.0\n", &[2, 9, 2]));
+}
+
+#[tokio::test]
+async fn get_preferences() {
+    let api = default_api().await.unwrap();
+    let prefs = api.get_preferences().await;
+    dbg! {&prefs};
+    prefs.unwrap();
+}
+
+#[tokio::test]
+async fn set_preferences() {
+    let api = default_api().await.unwrap();
+    let current = api.get_preferences().await.unwrap();
+
+    let update = data::PreferencesBuilder::default()
+        .dl_limit(current.dl_limit().unwrap_or(0))
+        .build()
+        .unwrap();
+    api.set_preferences(&update).await.unwrap();
+}
+
+#[test]
+fn credentials_debug_hides_password() {
+    let credentials = api::Credentials {
+        username: "admin".into(),
+        password: "hunter2".into(),
+    };
+    let debug = format!("{:?}", credentials);
+    assert!(debug.contains("admin"));
+    assert!(!debug.contains("hunter2"));
+}
+
+#[test]
+fn tracker_migration_rewrites() {
+    use migration::{TrackerMatch, TrackerMigration, TrackerRewrite};
+
+    let by_host = TrackerMigration::new(
+        TrackerMatch::Host("old.example.com".into()),
+        TrackerRewrite::Host("new.example.com".into()),
+    );
+    assert_eq!(
+        by_host.rewrite_url("https://OLD.example.com:443/abc123/announce"),
+        Some("https://new.example.com:443/abc123/announce".to_string())
+    );
+    assert_eq!(
+        by_host.rewrite_url("udp://user@old.example.com:6969?passkey=abc123"),
+        Some("udp://user@new.example.com:6969?passkey=abc123".to_string())
+    );
+    assert_eq!(by_host.rewrite_url("udp://other.example.com:80"), None);
+    assert_eq!(by_host.rewrite_url("** [DHT] **"), None);
+
+    let passkey = TrackerMigration::new(
+        TrackerMatch::Contains("old.example.com".into()),
+        TrackerRewrite::Replace {
+            from: "abc123".into(),
+            to: "def456".into(),
+        },
+    );
+    assert_eq!(
+        passkey.rewrite_url("https://old.example.com/abc123/announce"),
+        Some("https://old.example.com/def456/announce".to_string())
+    );
+    // matching urls that do not change are skipped
+    assert_eq!(
+        passkey.rewrite_url("https://old.example.com/announce"),
+        None
+    );
+
+    // rewrites that do not produce a url are not sent
+    let empty = TrackerMigration::new(
+        TrackerMatch::Contains("old.example.com".into()),
+        TrackerRewrite::Replace {
+            from: "".into(),
+            to: "x".into(),
+        },
+    );
+    assert_eq!(empty.rewrite_url("https://old.example.com/announce"), None);
+    let custom = TrackerMigration::new(
+        TrackerMatch::Contains("old.example.com".into()),
+        TrackerRewrite::Custom(Box::new(|url| url.replace("https://", ""))),
+    );
+    assert_eq!(custom.rewrite_url("https://old.example.com/announce"), None);
+}
+
+#[tokio::test]
+async fn tracker_migration_dry_run() {
+    let api = default_api().await.unwrap();
+    let migration = migration::TrackerMigration::new(
+        migration::TrackerMatch::Contains("://".into()),
+        migration::TrackerRewrite::Replace {
+            from: "announce".into(),
+            to: "scrape".into(),
+        },
+    )
+    .dry_run(true);
+
+    let reports = migration.run(&api).await.unwrap();
+    dbg! {&reports};
+    let all_planned = reports
+        .iter()
+        .flat_map(|x| x.changes())
+        .all(|x| matches!(x.status(), migration::ChangeStatus::Planned));
+    assert! {all_planned};
+}
+
+#[test]
+fn tracker_lenient_fields() {
+    let trackers: Vec<data::Tracker> = serde_json::from_value(serde_json::json!([
+        {"url": "** [DHT] **", "status": 2, "tier": "", "num_peers": 10, "num_seeds": 3,
+         "num_leeches": 7, "num_downloaded": -1, "msg": ""},
+        {"url": "** [PeX] **", "status": 2, "tier": "/", "num_peers": 0, "msg": ""},
+        {"url": "http://tracker.example.com/announce", "status": 2, "tier": 1,
+         "num_peers": 50, "num_seeds": 40, "num_leeches": "10", "num_downloaded": 300,
+         "msg": ""}
+    ]))
+    .unwrap();
+
+    assert_eq!(trackers[0].kind(), data::TrackerKind::Dht);
+    assert_eq!(*trackers[0].tier(), None);
+    assert_eq!(*trackers[0].num_seeds(), Some(3));
+    assert_eq!(*trackers[0].num_downloaded(), None);
+
+    assert!(trackers[1].is_pseudo());
+    assert_eq!(*trackers[1].tier(), None);
+    assert_eq!(*trackers[1].num_leeches(), None);
+
+    assert!(!trackers[2].is_pseudo());
+    assert_eq!(*trackers[2].tier(), Some(1));
+    assert_eq!(*trackers[2].num_leeches(), Some(10));
+    assert_eq!(*trackers[2].num_downloaded(), Some(300));
+}
+
+#[test]
+fn queue_plan_fewest_moves() {
+    let torrents: Vec<data::Torrent> = [("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 0)]
+        .iter()
+        .map(|(hash, priority)| {
+            let mut torrent = torrent_with_hash(hash);
+            torrent["priority"] = serde_json::json!(priority);
+            serde_json::from_value(torrent).unwrap()
+        })
+        .collect();
+    let hashes = |names: &[&str]| -> Vec<data::Hash> {
+        names
+            .iter()
+            .map(|x| data::Hash::from(x.to_string()))
+            .collect()
+    };
+    let top = |name: &str| queue::QueueMove::Top(data::Hash::from(name.to_string()));
+    let bottom = |name: &str| queue::QueueMove::Bottom(data::Hash::from(name.to_string()));
+
+    // already in order
+    assert!(queue::plan_queue_order(&torrents, &hashes(&["a", "b", "c", "d"])).is_empty());
+
+    // promote a single torrent
+    assert_eq!(
+        queue::plan_queue_order(&torrents, &hashes(&["d", "a", "b", "c"])),
+        vec![top("d")]
+    );
+
+    // b, c stay put, everything else moves around them. e is not queued
+    assert_eq!(
+        queue::plan_queue_order(&torrents, &hashes(&["d", "e", "b", "c", "a", "x"])),
+        vec![top("d"), bottom("a")]
+    );
+
+    // c, d are in relative order already, but not at the head of the queue
+    assert!(queue::plan_queue_order(&torrents, &hashes(&["c", "d"])).is_empty());
+    assert_eq!(
+        queue::plan_queue_top(&torrents, &hashes(&["c", "d"])),
+        vec![top("d"), top("c")]
+    );
+
+    // a, b already head the queue
+    assert!(queue::plan_queue_top(&torrents, &hashes(&["a", "b"])).is_empty());
+    assert_eq!(
+        queue::plan_queue_top(&torrents, &hashes(&["d", "a", "b"])),
+        vec![top("d")]
+    );
+}
+
+#[tokio::test]
+async fn queue_priority() {
+    let (api, torrent) = get_first_torrent().await;
+
+    match torrent.top_priority(&api).await {
+        // queueing is disabled on the server
+        Err(Error::Conflict(_)) => return,
+        other => other.unwrap(),
+    }
+    torrent.decrease_priority(&api).await.unwrap();
+    torrent.increase_priority(&api).await.unwrap();
+
+    api.reorder_queue(&[torrent.hash().clone()]).await.unwrap();
+    vec![torrent.hash().clone()]
+        .bottom_priority(&api)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn torrent_move_status() {
+    let torrent = |state: &str, save_path: &str| -> data::Torrent {
+        let mut torrent = torrent_with_hash("abc");
+        torrent["state"] = serde_json::json!(state);
+        torrent["save_path"] = serde_json::json!(save_path);
+        serde_json::from_value(torrent).unwrap()
+    };
+
+    assert!(matches!(
+        torrent("moving", "/ssd/").move_status("/hdd"),
+        data::MoveStatus::Moving
+    ));
+    assert!(matches!(
+        torrent("stalledUP", "/hdd/").move_status("/hdd"),
+        data::MoveStatus::Moved
+    ));
+    assert!(matches!(
+        torrent("stalledUP", "/ssd/").move_status("/hdd"),
+        data::MoveStatus::Failed { .. }
+    ));
+    assert!(matches!(
+        torrent("error", "/hdd/").move_status("/hdd"),
+        data::MoveStatus::Failed {
+            state: data::State::Error,
+            ..
+        }
+    ));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn set_location() {
+    let (api, torrent) = get_first_torrent().await;
+    let original = torrent.save_path().clone();
+    let interval = std::time::Duration::from_millis(500);
+
+    let location = "/tmp/qbittorrent-moved";
+    torrent.set_location(&api, location).await.unwrap();
+    let status = torrent
+        .hash()
+        .wait_for_move(&api, location, interval)
+        .await
+        .unwrap();
+    assert!(matches!(status, data::MoveStatus::Moved), "{:?}", status);
+
+    torrent.set_location(&api, &original).await.unwrap();
+    torrent
+        .hash()
+        .wait_for_move(&api, &original, interval)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn torrent_flags() {
+    let (api, torrent) = get_first_torrent().await;
+    let hash = torrent.hash().clone();
+    let sequential = *torrent.seq_dl();
+
+    // setting the current value is a no-op rather than a toggle
+    hash.set_sequential_download(&api, sequential)
+        .await
+        .unwrap();
+    hash.set_sequential_download(&api, !sequential)
+        .await
+        .unwrap();
+
+    let torrents = queries::TorrentRequestBuilder::default()
+        .hash(hash.clone())
+        .build()
+        .unwrap()
+        .send(&api)
+        .await
+        .unwrap();
+    assert_eq!(*torrents[0].seq_dl(), !sequential);
+
+    vec![hash.clone()]
+        .set_sequential_download(&api, sequential)
+        .await
+        .unwrap();
+    hash.set_force_start(&api, *torrent.force_start())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn rename_torrent() {
+    let (api, torrent) = get_first_torrent().await;
+    let name = torrent.name().clone();
+
+    torrent.rename(&api, "renamed by test").await.unwrap();
+    torrent.rename(&api, &name).await.unwrap();
+}
+
+#[test]
+fn torrent_tag_set() {
+    let mut torrent = torrent_with_hash("abc");
+    torrent["tags"] = serde_json::json!("tv, hd,,season pack");
+    let torrent: data::Torrent = serde_json::from_value(torrent).unwrap();
+
+    let tags: Vec<_> = torrent.tag_set().into_iter().collect();
+    assert_eq!(tags, vec!["hd", "season pack", "tv"]);
+
+    let untagged: data::Torrent = serde_json::from_value(torrent_with_hash("def")).unwrap();
+    assert!(untagged.tag_set().is_empty());
+}
+
+#[tokio::test]
+async fn tag_lifecycle() {
+    let (api, torrent) = get_first_torrent().await;
+    let tags = vec!["test tag".to_string(), "a&b".to_string()];
+
+    api.create_tags(&tags).await.unwrap();
+    let all = api.get_all_tags().await.unwrap();
+    assert!(tags.iter().all(|tag| all.contains(tag)));
+
+    vec![torrent.hash().clone()]
+        .add_tag(&api, &tags)
+        .await
+        .unwrap();
+    torrent.remove_tag(&api, &tags[..1]).await.unwrap();
+
+    api.delete_tags(&tags).await.unwrap();
+    let all = api.get_all_tags().await.unwrap();
+    assert!(!tags.iter().any(|tag| all.contains(tag)));
+}
+
+#[test]
+fn reconcile_plan() {
+    let spec: reconcile::Spec = serde_json::from_value(serde_json::json!({
+        "categories": {
+            "tv": {"save_path": "/data/tv"},
+            "movies": {"savePath": "/data/movies"},
+            "music/flac": {"save_path": "/data/flac"}
+        },
+        "tags": ["hd", "new"]
+    }))
+    .unwrap();
+
+    let live: std::collections::BTreeMap<String, data::Categories> =
+        serde_json::from_value(serde_json::json!({
+            "tv": {"name": "tv", "savePath": "/data/tv/"},
+            "movies": {"name": "movies", "savePath": "/old/movies", "download_path": false},
+            "music": {"name": "music", "savePath": "/data/music"},
+            "junk": {"name": "junk", "savePath": "/tmp"}
+        }))
+        .unwrap();
+    let tags = ["hd", "stale"].iter().map(|x| x.to_string()).collect();
+
+    let plan = spec.plan(&live, &tags);
+    assert_eq!(
+        plan.actions(),
+        &vec![
+            reconcile::Action::EditCategory {
+                name: "movies".into(),
+                from: "/old/movies".into(),
+                save_path: "/data/movies".into(),
+                download_path: data::CategoryDownloadPath::Disabled,
+            },
+            reconcile::Action::CreateCategory {
+                name: "music/flac".into(),
+                save_path: "/data/flac".into(),
+            },
+            reconcile::Action::RemoveCategory {
+                name: "junk".into()
+            },
+            reconcile::Action::CreateTag { name: "new".into() },
+            reconcile::Action::DeleteTag {
+                name: "stale".into()
+            },
+        ]
+    );
+
+    // without pruning only additions and edits are planned
+    let plan = spec.clone().prune(false).plan(&live, &tags);
+    assert_eq!(plan.actions().len(), 3);
+
+    let converged = reconcile::Spec::new()
+        .category("tv", "/data/tv")
+        .tag("hd")
+        .prune(false);
+    assert!(converged.plan(&live, &tags).is_empty());
+
+    // a section left out of the spec is not pruned
+    let categories_only: reconcile::Spec = serde_json::from_value(serde_json::json!({
+        "categories": {
+            "tv": {"save_path": "/data/tv"},
+            "movies": {"save_path": "/old/movies"},
+            "music": {"save_path": "/data/music"},
+            "junk": {"save_path": "/tmp"}
+        }
+    }))
+    .unwrap();
+    assert!(categories_only.plan(&live, &tags).is_empty());
+}
+
+#[tokio::test]
+async fn reconcile() {
+    let api = default_api().await.unwrap();
+    let spec = reconcile::Spec::new()
+        .category("reconcile_test", "/tmp/reconcile")
+        .tag("reconcile_test")
+        .prune(false);
+
+    api.reconcile(&spec).await.unwrap();
+    assert!(api.plan_reconcile(&spec).await.unwrap().is_empty());
+
+    api.remove_categories(&["reconcile_test".to_string()])
+        .await
+        .unwrap();
+    api.delete_tags(&["reconcile_test".to_string()])
+        .await
+        .unwrap();
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use async_trait::async_trait;
 
 use serde::Serialize;
@@ -5,7 +7,9 @@ use serde::Serialize;
 use super::api::Api;
 use super::data::*;
 use super::error::Error;
-use super::utils::QueryConcat;
+use super::queries::{ShareLimitsRequest, TorrentRequestBuilder};
+use super::sync::PeersSync;
+use super::utils::{check_status, QueryConcat};
 
 #[async_trait]
 pub trait TorrentData<T> {
@@ -14,6 +18,17 @@ pub trait TorrentData<T> {
     // TOOD: when impl'd on Api, self is 'a when it does not need to be
     // when impl'd on Torrent, other is &Api which also does not need to be 'a
     async fn contents<'a>(&'a self, other: &'a T) -> Result<Vec<TorrentInfo<'a>>, Error>;
+    /// State of every piece of the torrent, in order
+    async fn piece_states(&self, other: &'_ T) -> Result<Vec<PieceState>, Error>;
+    /// SHA-1 hash of every piece of the torrent, in order
+    async fn piece_hashes(&self, other: &'_ T) -> Result<Vec<String>, Error>;
+    async fn web_seeds(&self, other: &'_ T) -> Result<Vec<WebSeed>, Error>;
+}
+
+#[async_trait]
+/// Track the peers connected to a torrent through `sync/torrentPeers`
+pub trait Peers<T> {
+    async fn sync_peers(&self, other: &'_ T, state: &mut PeersSync) -> Result<(), Error>;
 }
 
 #[async_trait]
@@ -24,6 +39,8 @@ pub trait Recheck<T> {
 
 #[async_trait]
 /// Assist in setting a category for a torrent
+///
+/// Fails with `Error::Conflict` if the category does not exist
 pub trait Category<T> {
     async fn set_category(&self, other: &'_ T, category: &str) -> Result<(), Error>;
 }
@@ -40,32 +57,151 @@ pub trait Pause<T> {
 }
 
 #[async_trait]
-/// Add a tag to a torrent
+/// Remove a torrent from qbittorrent, optionally deleting its downloaded data
+pub trait Delete<T> {
+    async fn delete(&self, other: &'_ T, delete_files: bool) -> Result<(), Error>;
+}
+
+#[async_trait]
+/// Move torrents in the download queue
+///
+/// Fails with `Error::Conflict` if queueing is disabled
+pub trait Queue<T> {
+    /// Move up one position
+    async fn increase_priority(&self, other: &'_ T) -> Result<(), Error>;
+    /// Move down one position
+    async fn decrease_priority(&self, other: &'_ T) -> Result<(), Error>;
+    /// Move to the top of the queue
+    async fn top_priority(&self, other: &'_ T) -> Result<(), Error>;
+    /// Move to the bottom of the queue
+    async fn bottom_priority(&self, other: &'_ T) -> Result<(), Error>;
+}
+
+#[async_trait]
+/// Read and change the speed limits of torrents
+///
+/// Limits are returned keyed by the hash of each torrent
+pub trait SpeedLimits<T> {
+    async fn download_limit(&self, other: &'_ T) -> Result<BTreeMap<String, SpeedLimit>, Error>;
+    async fn set_download_limit(&self, other: &'_ T, limit: SpeedLimit) -> Result<(), Error>;
+    async fn upload_limit(&self, other: &'_ T) -> Result<BTreeMap<String, SpeedLimit>, Error>;
+    async fn set_upload_limit(&self, other: &'_ T, limit: SpeedLimit) -> Result<(), Error>;
+}
+
+#[async_trait]
+/// Set the ratio and seeding time limits of torrents
+pub trait ShareLimits<T> {
+    async fn set_share_limits(
+        &self,
+        other: &'_ T,
+        limits: &ShareLimitsRequest,
+    ) -> Result<(), Error>;
+}
+
+#[async_trait]
+/// Move the data of torrents to another directory
+///
+/// Moving happens in the background, use `Hash::move_status` or `Hash::wait_for_move` to
+/// find out when it is done.
+pub trait Location<T> {
+    /// Fails with `Error::Conflict` if the directory can not be created and
+    /// `Error::AccessDenied` if it is not writable
+    async fn set_location(&self, other: &'_ T, location: &str) -> Result<(), Error>;
+    /// Only supported by WebUI API 2.8.4 (qbittorrent 4.4) and later
+    async fn set_save_path(&self, other: &'_ T, path: &str) -> Result<(), Error>;
+    /// Where incomplete torrents are stored. Only supported by WebUI API 2.8.4
+    /// (qbittorrent 4.4) and later
+    async fn set_download_path(&self, other: &'_ T, path: &str) -> Result<(), Error>;
+}
+
+#[async_trait]
+/// Change the display name of a torrent
+pub trait Rename<T> {
+    async fn rename(&self, other: &'_ T, name: &str) -> Result<(), Error>;
+}
+
+#[async_trait]
+/// Turn per-torrent behaviour on or off
+///
+/// Each method takes the desired end state. Sequential download and first / last piece
+/// priority can only be toggled by qbittorrent, so the current values are read first and
+/// only the torrents that differ are toggled.
+pub trait Flags<T> {
+    async fn set_sequential_download(&self, other: &'_ T, enabled: bool) -> Result<(), Error>;
+    async fn set_first_last_piece_priority(&self, other: &'_ T, enabled: bool)
+        -> Result<(), Error>;
+    async fn set_force_start(&self, other: &'_ T, enabled: bool) -> Result<(), Error>;
+    async fn set_super_seeding(&self, other: &'_ T, enabled: bool) -> Result<(), Error>;
+    async fn set_auto_management(&self, other: &'_ T, enabled: bool) -> Result<(), Error>;
+}
+
+#[async_trait]
+/// Add, edit and remove the trackers of a torrent
+///
+/// Tracker urls are the same as `Tracker::url` returned from `TorrentData::trackers`
+pub trait Trackers<T> {
+    async fn add_trackers(&self, other: &'_ T, urls: &[String]) -> Result<(), Error>;
+    /// Replace the tracker `orig_url` with `new_url`
+    ///
+    /// Fails with `Error::Conflict` if `new_url` is already a tracker or `orig_url` is not
+    async fn edit_tracker(&self, other: &'_ T, orig_url: &str, new_url: &str) -> Result<(), Error>;
+    async fn remove_trackers(&self, other: &'_ T, urls: &[String]) -> Result<(), Error>;
+}
+
+#[async_trait]
+/// Add, edit and remove the web seeds of a torrent
+///
+/// Only supported by WebUI API 2.9.3 (qbittorrent 5.0) and later, older versions fail with
+/// `Error::NotFound`.
+pub trait WebSeeds<T> {
+    async fn add_web_seeds(&self, other: &'_ T, urls: &[String]) -> Result<(), Error>;
+    /// Replace the web seed `orig_url` with `new_url`
+    ///
+    /// Fails with `Error::Conflict` if `new_url` is already a web seed or `orig_url` is not
+    async fn edit_web_seed(&self, other: &'_ T, orig_url: &str, new_url: &str)
+        -> Result<(), Error>;
+    async fn remove_web_seeds(&self, other: &'_ T, urls: &[String]) -> Result<(), Error>;
+}
+
+#[async_trait]
+/// Choose which files of a torrent are downloaded and rename them
+///
+/// Files are identified by `TorrentInfo::index` as returned from `TorrentData::contents`,
+/// paths are relative to the torrent's root.
+pub trait Files<T> {
+    async fn set_file_priority(
+        &self,
+        other: &'_ T,
+        files: &[usize],
+        priority: FilePriority,
+    ) -> Result<(), Error>;
+    async fn rename_file(&self, other: &'_ T, old_path: &str, new_path: &str) -> Result<(), Error>;
+    async fn rename_folder(
+        &self,
+        other: &'_ T,
+        old_path: &str,
+        new_path: &str,
+    ) -> Result<(), Error>;
+}
+
+#[async_trait]
+/// Add tags to and remove tags from a torrent
+///
+/// Tags that do not exist yet are created by `add_tag`. Removing a tag from a torrent does
+/// not delete it, see `Api::delete_tags`.
 pub trait Tags<T, V: ?Sized> {
     async fn add_tag(&self, other: &'_ T, tags: &'_ V) -> Result<(), Error>;
+    async fn remove_tag(&self, other: &'_ T, tags: &'_ V) -> Result<(), Error>;
 }
 
 #[async_trait]
 impl Category<Api> for Torrent {
     async fn set_category(&self, api: &'_ Api, category: &str) -> Result<(), Error> {
-        let addr = push_own!(
-            api.address,
-            "/api/v2/torrents/setCategory?hashes=",
-            &self.hash,
-            "&category=",
-            category
-        );
-
-        let res = api
-            .client
-            .get(&addr)
-            .headers(api.make_headers()?)
-            .send()
-            .await?
-            .bytes()
-            .await?;
-        dbg! {res};
+        let addr = push_own!(api.address, "/api/v2/torrents/setCategory");
+        let form = [("hashes", self.hash.as_str()), ("category", category)];
 
+        let res = api.send(|client| client.post(&addr).form(&form)).await?;
+        check_status(res).await?;
         Ok(())
     }
 }
@@ -73,7 +209,7 @@ impl Category<Api> for Torrent {
 #[async_trait]
 impl TorrentData<Api> for Torrent {
     async fn properties(&self, api: &'_ Api) -> Result<TorrentProperties, Error> {
-        self.hash.properties(&api).await
+        self.hash.properties(api).await
     }
 
     async fn trackers(&self, api: &'_ Api) -> Result<Vec<Tracker>, Error> {
@@ -83,6 +219,18 @@ impl TorrentData<Api> for Torrent {
     async fn contents<'a>(&'a self, api: &'a Api) -> Result<Vec<TorrentInfo<'a>>, Error> {
         self.hash.contents(api).await
     }
+
+    async fn piece_states(&self, api: &'_ Api) -> Result<Vec<PieceState>, Error> {
+        self.hash.piece_states(api).await
+    }
+
+    async fn piece_hashes(&self, api: &'_ Api) -> Result<Vec<String>, Error> {
+        self.hash.piece_hashes(api).await
+    }
+
+    async fn web_seeds(&self, api: &'_ Api) -> Result<Vec<WebSeed>, Error> {
+        self.hash.web_seeds(api).await
+    }
 }
 
 #[async_trait]
@@ -91,14 +239,7 @@ impl TorrentData<Api> for Hash {
         let _hash = &self.hash;
         let addr = push_own! {api.address, "/api/v2/torrents/properties?hash=", self};
 
-        let res = api
-            .client
-            .get(&addr)
-            .headers(api.make_headers()?)
-            .send()
-            .await?
-            .bytes()
-            .await?;
+        let res = api.send(|client| client.get(&addr)).await?.bytes().await?;
 
         let props = serde_json::from_slice(&res)?;
         Ok(props)
@@ -107,14 +248,7 @@ impl TorrentData<Api> for Hash {
     async fn trackers(&self, api: &'_ Api) -> Result<Vec<Tracker>, Error> {
         let addr = push_own! {api.address, "/api/v2/torrents/trackers?hash=", self};
 
-        let res = api
-            .client
-            .get(&addr)
-            .headers(api.make_headers()?)
-            .send()
-            .await?
-            .bytes()
-            .await?;
+        let res = api.send(|client| client.get(&addr)).await?.bytes().await?;
 
         let trackers = serde_json::from_slice(&res)?;
         Ok(trackers)
@@ -123,22 +257,46 @@ impl TorrentData<Api> for Hash {
     async fn contents<'a>(&'a self, api: &'a Api) -> Result<Vec<TorrentInfo<'a>>, Error> {
         let addr = push_own! {api.address, "/api/v2/torrents/files?hash=", self};
 
-        let res = api
-            .client
-            .get(&addr)
-            .headers(api.make_headers()?)
-            .send()
-            .await?
-            .bytes()
-            .await?;
+        let res = api.send(|client| client.get(&addr)).await?.bytes().await?;
 
         let info = serde_json::from_slice::<Vec<TorrentInfoSerde>>(&res)?
             .into_iter()
-            .map(|x| x.into_info(self))
+            .enumerate()
+            .map(|(position, x)| x.into_info(self, position))
             .collect();
 
         Ok(info)
     }
+
+    async fn piece_states(&self, api: &'_ Api) -> Result<Vec<PieceState>, Error> {
+        let addr = push_own! {api.address, "/api/v2/torrents/pieceStates?hash=", self};
+
+        let res = api.send(|client| client.get(&addr)).await?;
+        let res = check_status(res).await?.bytes().await?;
+
+        let states = serde_json::from_slice(&res)?;
+        Ok(states)
+    }
+
+    async fn piece_hashes(&self, api: &'_ Api) -> Result<Vec<String>, Error> {
+        let addr = push_own! {api.address, "/api/v2/torrents/pieceHashes?hash=", self};
+
+        let res = api.send(|client| client.get(&addr)).await?;
+        let res = check_status(res).await?.bytes().await?;
+
+        let hashes = serde_json::from_slice(&res)?;
+        Ok(hashes)
+    }
+
+    async fn web_seeds(&self, api: &'_ Api) -> Result<Vec<WebSeed>, Error> {
+        let addr = push_own! {api.address, "/api/v2/torrents/webseeds?hash=", self};
+
+        let res = api.send(|client| client.get(&addr)).await?;
+        let res = check_status(res).await?.bytes().await?;
+
+        let seeds = serde_json::from_slice(&res)?;
+        Ok(seeds)
+    }
 }
 
 #[async_trait]
@@ -154,12 +312,24 @@ impl TorrentData<Torrent> for Api {
     async fn contents<'a>(&'a self, torrent: &'a Torrent) -> Result<Vec<TorrentInfo<'a>>, Error> {
         torrent.hash.contents(&self).await
     }
+
+    async fn piece_states(&self, torrent: &'_ Torrent) -> Result<Vec<PieceState>, Error> {
+        torrent.hash.piece_states(self).await
+    }
+
+    async fn piece_hashes(&self, torrent: &'_ Torrent) -> Result<Vec<String>, Error> {
+        torrent.hash.piece_hashes(self).await
+    }
+
+    async fn web_seeds(&self, torrent: &'_ Torrent) -> Result<Vec<WebSeed>, Error> {
+        torrent.hash.web_seeds(self).await
+    }
 }
 
 #[async_trait]
 impl Resume<Api> for Torrent {
     async fn resume(&self, api: &'_ Api) -> Result<(), Error> {
-        self.hash.resume(&api).await
+        self.hash.resume(api).await
     }
 }
 
@@ -169,12 +339,7 @@ impl Resume<Api> for Hash {
         let _hash = &self.hash;
         let addr = push_own! {api.address, "/api/v2/torrents/resume?hashes=", _hash};
 
-        let res = api
-            .client
-            .get(&addr)
-            .headers(api.make_headers()?)
-            .send()
-            .await?;
+        let res = api.send(|client| client.get(&addr)).await?;
 
         match res.error_for_status() {
             Ok(_) => Ok(()),
@@ -190,12 +355,7 @@ impl Resume<Api> for Vec<Hash> {
 
         let addr = push_own! {api.address, "/api/v2/torrents/resume?hashes=", &hash_url};
 
-        let res = api
-            .client
-            .get(&addr)
-            .headers(api.make_headers()?)
-            .send()
-            .await?;
+        let res = api.send(|client| client.get(&addr)).await?;
 
         match res.error_for_status() {
             Ok(_) => Ok(()),
@@ -207,7 +367,7 @@ impl Resume<Api> for Vec<Hash> {
 #[async_trait]
 impl Pause<Api> for Torrent {
     async fn pause(&self, api: &'_ Api) -> Result<(), Error> {
-        self.hash.pause(&api).await
+        self.hash.pause(api).await
     }
 }
 
@@ -217,12 +377,7 @@ impl Pause<Api> for Hash {
         let _hash = &self.hash;
         let addr = push_own! {api.address, "/api/v2/torrents/pause?hashes=", _hash};
 
-        let res = api
-            .client
-            .get(&addr)
-            .headers(api.make_headers()?)
-            .send()
-            .await?;
+        let res = api.send(|client| client.get(&addr)).await?;
 
         match res.error_for_status() {
             Ok(_) => Ok(()),
@@ -239,7 +394,7 @@ impl Pause<Api> for Vec<Hash> {
             .iter()
             .map(|x| {
                 let mut cln = x.hash.clone();
-                cln.push_str("|");
+                cln.push('|');
                 cln
             })
             .collect::<String>();
@@ -249,12 +404,7 @@ impl Pause<Api> for Vec<Hash> {
 
         let addr = push_own! {api.address, "/api/v2/torrents/pause?hashes=", &hash_url};
 
-        let res = api
-            .client
-            .get(&addr)
-            .headers(api.make_headers()?)
-            .send()
-            .await?;
+        let res = api.send(|client| client.get(&addr)).await?;
 
         match res.error_for_status() {
             Ok(_) => Ok(()),
@@ -264,50 +414,1035 @@ impl Pause<Api> for Vec<Hash> {
 }
 
 #[derive(Serialize)]
-struct TagsUrlHelper<'hash, 'tag> {
-    hashes: &'hash [&'hash Hash],
-    tags: &'tag [String],
+struct DeleteForm<'a> {
+    hashes: &'a str,
+    #[serde(rename = "deleteFiles")]
+    delete_files: bool,
+}
+
+impl<'a> DeleteForm<'a> {
+    async fn send(self, api: &'_ Api) -> Result<(), Error> {
+        let addr = push_own! {api.address, "/api/v2/torrents/delete"};
+
+        let res = api.send(|client| client.post(&addr).form(&self)).await?;
+
+        match res.error_for_status() {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::from(e)),
+        }
+    }
+}
+
+#[async_trait]
+impl Delete<Api> for Torrent {
+    async fn delete(&self, api: &'_ Api, delete_files: bool) -> Result<(), Error> {
+        self.hash.delete(api, delete_files).await
+    }
+}
+
+#[async_trait]
+impl Delete<Api> for Hash {
+    async fn delete(&self, api: &'_ Api, delete_files: bool) -> Result<(), Error> {
+        let form = DeleteForm {
+            hashes: &self.hash,
+            delete_files,
+        };
+        form.send(api).await
+    }
+}
+
+#[async_trait]
+impl Delete<Api> for Vec<Hash> {
+    async fn delete(&self, api: &'_ Api, delete_files: bool) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        let form = DeleteForm {
+            hashes: &hash_url,
+            delete_files,
+        };
+        form.send(api).await
+    }
+}
+
+#[async_trait]
+impl Delete<Api> for AllTorrents {
+    async fn delete(&self, api: &'_ Api, delete_files: bool) -> Result<(), Error> {
+        let form = DeleteForm {
+            hashes: "all",
+            delete_files,
+        };
+        form.send(api).await
+    }
+}
+
+#[derive(Serialize)]
+struct HashesForm<'a> {
+    hashes: &'a str,
+}
+
+impl<'a> HashesForm<'a> {
+    async fn send(self, api: &'_ Api, endpoint: &str) -> Result<(), Error> {
+        let addr = push_own! {api.address, endpoint};
+
+        let res = api.send(|client| client.post(&addr).form(&self)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Queue<Api> for Torrent {
+    async fn increase_priority(&self, api: &'_ Api) -> Result<(), Error> {
+        self.hash.increase_priority(api).await
+    }
+
+    async fn decrease_priority(&self, api: &'_ Api) -> Result<(), Error> {
+        self.hash.decrease_priority(api).await
+    }
+
+    async fn top_priority(&self, api: &'_ Api) -> Result<(), Error> {
+        self.hash.top_priority(api).await
+    }
+
+    async fn bottom_priority(&self, api: &'_ Api) -> Result<(), Error> {
+        self.hash.bottom_priority(api).await
+    }
+}
+
+#[async_trait]
+impl Queue<Api> for Hash {
+    async fn increase_priority(&self, api: &'_ Api) -> Result<(), Error> {
+        let form = HashesForm { hashes: &self.hash };
+        form.send(api, "/api/v2/torrents/increasePrio").await
+    }
+
+    async fn decrease_priority(&self, api: &'_ Api) -> Result<(), Error> {
+        let form = HashesForm { hashes: &self.hash };
+        form.send(api, "/api/v2/torrents/decreasePrio").await
+    }
+
+    async fn top_priority(&self, api: &'_ Api) -> Result<(), Error> {
+        let form = HashesForm { hashes: &self.hash };
+        form.send(api, "/api/v2/torrents/topPrio").await
+    }
+
+    async fn bottom_priority(&self, api: &'_ Api) -> Result<(), Error> {
+        let form = HashesForm { hashes: &self.hash };
+        form.send(api, "/api/v2/torrents/bottomPrio").await
+    }
+}
+
+/// The torrents keep their relative order, e.g. `top_priority` moves all of them to the top
+/// of the queue in the order they were queued before.
+#[async_trait]
+impl Queue<Api> for Vec<Hash> {
+    async fn increase_priority(&self, api: &'_ Api) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        let form = HashesForm { hashes: &hash_url };
+        form.send(api, "/api/v2/torrents/increasePrio").await
+    }
+
+    async fn decrease_priority(&self, api: &'_ Api) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        let form = HashesForm { hashes: &hash_url };
+        form.send(api, "/api/v2/torrents/decreasePrio").await
+    }
+
+    async fn top_priority(&self, api: &'_ Api) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        let form = HashesForm { hashes: &hash_url };
+        form.send(api, "/api/v2/torrents/topPrio").await
+    }
+
+    async fn bottom_priority(&self, api: &'_ Api) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        let form = HashesForm { hashes: &hash_url };
+        form.send(api, "/api/v2/torrents/bottomPrio").await
+    }
+}
+
+#[derive(Serialize)]
+struct SpeedLimitForm<'a> {
+    hashes: &'a str,
+    limit: SpeedLimit,
+}
+
+async fn get_speed_limits(
+    api: &'_ Api,
+    hashes: &str,
+    endpoint: &str,
+) -> Result<BTreeMap<String, SpeedLimit>, Error> {
+    let addr = push_own! {api.address, endpoint};
+    let form = HashesForm { hashes };
+
+    let res = api.send(|client| client.post(&addr).form(&form)).await?;
+    let res = check_status(res).await?.bytes().await?;
+
+    let limits = serde_json::from_slice(&res)?;
+    Ok(limits)
+}
+
+async fn set_speed_limit(
+    api: &'_ Api,
+    hashes: &str,
+    endpoint: &str,
+    limit: SpeedLimit,
+) -> Result<(), Error> {
+    let addr = push_own! {api.address, endpoint};
+    let form = SpeedLimitForm { hashes, limit };
+
+    let res = api.send(|client| client.post(&addr).form(&form)).await?;
+    check_status(res).await?;
+    Ok(())
+}
+
+#[async_trait]
+impl SpeedLimits<Api> for Torrent {
+    async fn download_limit(&self, api: &'_ Api) -> Result<BTreeMap<String, SpeedLimit>, Error> {
+        self.hash.download_limit(api).await
+    }
+
+    async fn set_download_limit(&self, api: &'_ Api, limit: SpeedLimit) -> Result<(), Error> {
+        self.hash.set_download_limit(api, limit).await
+    }
+
+    async fn upload_limit(&self, api: &'_ Api) -> Result<BTreeMap<String, SpeedLimit>, Error> {
+        self.hash.upload_limit(api).await
+    }
+
+    async fn set_upload_limit(&self, api: &'_ Api, limit: SpeedLimit) -> Result<(), Error> {
+        self.hash.set_upload_limit(api, limit).await
+    }
+}
+
+#[async_trait]
+impl SpeedLimits<Api> for Hash {
+    async fn download_limit(&self, api: &'_ Api) -> Result<BTreeMap<String, SpeedLimit>, Error> {
+        get_speed_limits(api, &self.hash, "/api/v2/torrents/downloadLimit").await
+    }
+
+    async fn set_download_limit(&self, api: &'_ Api, limit: SpeedLimit) -> Result<(), Error> {
+        set_speed_limit(api, &self.hash, "/api/v2/torrents/setDownloadLimit", limit).await
+    }
+
+    async fn upload_limit(&self, api: &'_ Api) -> Result<BTreeMap<String, SpeedLimit>, Error> {
+        get_speed_limits(api, &self.hash, "/api/v2/torrents/uploadLimit").await
+    }
+
+    async fn set_upload_limit(&self, api: &'_ Api, limit: SpeedLimit) -> Result<(), Error> {
+        set_speed_limit(api, &self.hash, "/api/v2/torrents/setUploadLimit", limit).await
+    }
+}
+
+#[async_trait]
+impl SpeedLimits<Api> for Vec<Hash> {
+    async fn download_limit(&self, api: &'_ Api) -> Result<BTreeMap<String, SpeedLimit>, Error> {
+        if self.is_empty() {
+            return Ok(BTreeMap::new());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        get_speed_limits(api, &hash_url, "/api/v2/torrents/downloadLimit").await
+    }
+
+    async fn set_download_limit(&self, api: &'_ Api, limit: SpeedLimit) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        set_speed_limit(api, &hash_url, "/api/v2/torrents/setDownloadLimit", limit).await
+    }
+
+    async fn upload_limit(&self, api: &'_ Api) -> Result<BTreeMap<String, SpeedLimit>, Error> {
+        if self.is_empty() {
+            return Ok(BTreeMap::new());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        get_speed_limits(api, &hash_url, "/api/v2/torrents/uploadLimit").await
+    }
+
+    async fn set_upload_limit(&self, api: &'_ Api, limit: SpeedLimit) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        set_speed_limit(api, &hash_url, "/api/v2/torrents/setUploadLimit", limit).await
+    }
+}
+
+async fn set_share_limits(
+    api: &'_ Api,
+    hashes: &str,
+    limits: &ShareLimitsRequest,
+) -> Result<(), Error> {
+    let addr = push_own! {api.address, "/api/v2/torrents/setShareLimits"};
+    let form = limits.form(hashes);
+
+    let res = api.send(|client| client.post(&addr).form(&form)).await?;
+    check_status(res).await?;
+    Ok(())
+}
+
+#[async_trait]
+impl ShareLimits<Api> for Torrent {
+    async fn set_share_limits(
+        &self,
+        api: &'_ Api,
+        limits: &ShareLimitsRequest,
+    ) -> Result<(), Error> {
+        self.hash.set_share_limits(api, limits).await
+    }
+}
+
+#[async_trait]
+impl ShareLimits<Api> for Hash {
+    async fn set_share_limits(
+        &self,
+        api: &'_ Api,
+        limits: &ShareLimitsRequest,
+    ) -> Result<(), Error> {
+        set_share_limits(api, &self.hash, limits).await
+    }
+}
+
+#[async_trait]
+impl ShareLimits<Api> for Vec<Hash> {
+    async fn set_share_limits(
+        &self,
+        api: &'_ Api,
+        limits: &ShareLimitsRequest,
+    ) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        set_share_limits(api, &hash_url, limits).await
+    }
+}
+
+#[derive(Serialize)]
+struct SetLocationForm<'a> {
+    hashes: &'a str,
+    location: &'a str,
+}
+
+#[derive(Serialize)]
+struct SetPathForm<'a> {
+    id: &'a str,
+    path: &'a str,
+}
+
+async fn set_location(api: &'_ Api, hashes: &str, location: &str) -> Result<(), Error> {
+    let addr = push_own! {api.address, "/api/v2/torrents/setLocation"};
+    let form = SetLocationForm { hashes, location };
+
+    let res = api.send(|client| client.post(&addr).form(&form)).await?;
+    check_status(res).await?;
+    Ok(())
+}
+
+async fn set_path(api: &'_ Api, hashes: &str, endpoint: &str, path: &str) -> Result<(), Error> {
+    let addr = push_own! {api.address, endpoint};
+    let form = SetPathForm { id: hashes, path };
+
+    let res = api.send(|client| client.post(&addr).form(&form)).await?;
+    check_status(res).await?;
+    Ok(())
+}
+
+#[async_trait]
+impl Location<Api> for Torrent {
+    async fn set_location(&self, api: &'_ Api, location: &str) -> Result<(), Error> {
+        self.hash.set_location(api, location).await
+    }
+
+    async fn set_save_path(&self, api: &'_ Api, path: &str) -> Result<(), Error> {
+        self.hash.set_save_path(api, path).await
+    }
+
+    async fn set_download_path(&self, api: &'_ Api, path: &str) -> Result<(), Error> {
+        self.hash.set_download_path(api, path).await
+    }
+}
+
+#[async_trait]
+impl Location<Api> for Hash {
+    async fn set_location(&self, api: &'_ Api, location: &str) -> Result<(), Error> {
+        set_location(api, &self.hash, location).await
+    }
+
+    async fn set_save_path(&self, api: &'_ Api, path: &str) -> Result<(), Error> {
+        set_path(api, &self.hash, "/api/v2/torrents/setSavePath", path).await
+    }
+
+    async fn set_download_path(&self, api: &'_ Api, path: &str) -> Result<(), Error> {
+        set_path(api, &self.hash, "/api/v2/torrents/setDownloadPath", path).await
+    }
+}
+
+#[async_trait]
+impl Location<Api> for Vec<Hash> {
+    async fn set_location(&self, api: &'_ Api, location: &str) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        set_location(api, &hash_url, location).await
+    }
+
+    async fn set_save_path(&self, api: &'_ Api, path: &str) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        set_path(api, &hash_url, "/api/v2/torrents/setSavePath", path).await
+    }
+
+    async fn set_download_path(&self, api: &'_ Api, path: &str) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        set_path(api, &hash_url, "/api/v2/torrents/setDownloadPath", path).await
+    }
+}
+
+impl Hash {
+    /// Check on a move of this torrent to `location`
+    ///
+    /// Fails with `Error::NotFound` if the torrent no longer exists
+    pub async fn move_status(&self, api: &Api, location: &str) -> Result<MoveStatus, Error> {
+        let torrent = TorrentRequestBuilder::default()
+            .hash(self.clone())
+            .build()
+            .expect("every field of a torrent request has a default")
+            .send(api)
+            .await?
+            .pop()
+            .ok_or_else(|| Error::NotFound(format!("torrent {} not found", self.hash)))?;
+
+        Ok(torrent.move_status(location))
+    }
+
+    /// Poll `move_status` every `interval` until the torrent is no longer moving
+    #[cfg(feature = "tokio")]
+    pub async fn wait_for_move(
+        &self,
+        api: &Api,
+        location: &str,
+        interval: std::time::Duration,
+    ) -> Result<MoveStatus, Error> {
+        loop {
+            match self.move_status(api, location).await? {
+                MoveStatus::Moving => tokio::time::sleep(interval).await,
+                status => return Ok(status),
+            }
+        }
+    }
+}
+
+impl Torrent {
+    /// Whether a move of this torrent to `location` is still running, done or failed
+    pub fn move_status(&self, location: &str) -> MoveStatus {
+        let trim = |path: &str| path.trim_end_matches(['/', '\\']).to_string();
+
+        match self.state() {
+            State::Moving => MoveStatus::Moving,
+            State::Error | State::MissingFiles => MoveStatus::Failed {
+                state: self.state().clone(),
+                save_path: self.save_path().clone(),
+            },
+            _ if trim(self.save_path()) == trim(location) => MoveStatus::Moved,
+            _ => MoveStatus::Failed {
+                state: self.state().clone(),
+                save_path: self.save_path().clone(),
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct RenameForm<'a> {
+    hash: &'a str,
+    name: &'a str,
+}
+
+#[async_trait]
+impl Rename<Api> for Torrent {
+    async fn rename(&self, api: &'_ Api, name: &str) -> Result<(), Error> {
+        self.hash.rename(api, name).await
+    }
+}
+
+#[async_trait]
+impl Rename<Api> for Hash {
+    async fn rename(&self, api: &'_ Api, name: &str) -> Result<(), Error> {
+        let addr = push_own! {api.address, "/api/v2/torrents/rename"};
+        let form = RenameForm {
+            hash: &self.hash,
+            name,
+        };
+
+        let res = api.send(|client| client.post(&addr).form(&form)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct FlagForm<'a> {
+    hashes: &'a str,
+    value: bool,
+}
+
+#[derive(Serialize)]
+struct AutoManagementForm<'a> {
+    hashes: &'a str,
+    enable: bool,
+}
+
+/// Toggle only the torrents of `hashes` where `flag` is not already `enabled`
+async fn toggle_to(
+    api: &'_ Api,
+    hashes: &str,
+    endpoint: &str,
+    enabled: bool,
+    flag: fn(&Torrent) -> bool,
+) -> Result<(), Error> {
+    let torrents = TorrentRequestBuilder::default()
+        .hash(Hash::from(hashes.to_string()))
+        .build()
+        .expect("every field of a torrent request has a default")
+        .send(api)
+        .await?;
+
+    let toggle: Vec<Hash> = torrents
+        .iter()
+        .filter(|torrent| flag(torrent) != enabled)
+        .map(|torrent| torrent.hash.clone())
+        .collect();
+
+    if toggle.is_empty() {
+        return Ok(());
+    }
+
+    let hash_url = QueryConcat::query_concat(&toggle.as_slice(), '|');
+    let form = HashesForm { hashes: &hash_url };
+    form.send(api, endpoint).await
+}
+
+async fn set_flags(api: &'_ Api, hashes: &str, endpoint: &str, value: bool) -> Result<(), Error> {
+    let addr = push_own! {api.address, endpoint};
+    let form = FlagForm { hashes, value };
+
+    let res = api.send(|client| client.post(&addr).form(&form)).await?;
+    check_status(res).await?;
+    Ok(())
+}
+
+async fn set_auto_management(api: &'_ Api, hashes: &str, enable: bool) -> Result<(), Error> {
+    let addr = push_own! {api.address, "/api/v2/torrents/setAutoManagement"};
+    let form = AutoManagementForm { hashes, enable };
+
+    let res = api.send(|client| client.post(&addr).form(&form)).await?;
+    check_status(res).await?;
+    Ok(())
+}
+
+fn sequential_download(torrent: &Torrent) -> bool {
+    *torrent.seq_dl()
+}
+
+fn first_last_piece_priority(torrent: &Torrent) -> bool {
+    torrent.f_l_piece_prio().unwrap_or(false)
+}
+
+#[async_trait]
+impl Flags<Api> for Torrent {
+    async fn set_sequential_download(&self, api: &'_ Api, enabled: bool) -> Result<(), Error> {
+        self.hash.set_sequential_download(api, enabled).await
+    }
+
+    async fn set_first_last_piece_priority(
+        &self,
+        api: &'_ Api,
+        enabled: bool,
+    ) -> Result<(), Error> {
+        self.hash.set_first_last_piece_priority(api, enabled).await
+    }
+
+    async fn set_force_start(&self, api: &'_ Api, enabled: bool) -> Result<(), Error> {
+        self.hash.set_force_start(api, enabled).await
+    }
+
+    async fn set_super_seeding(&self, api: &'_ Api, enabled: bool) -> Result<(), Error> {
+        self.hash.set_super_seeding(api, enabled).await
+    }
+
+    async fn set_auto_management(&self, api: &'_ Api, enabled: bool) -> Result<(), Error> {
+        self.hash.set_auto_management(api, enabled).await
+    }
+}
+
+#[async_trait]
+impl Flags<Api> for Hash {
+    async fn set_sequential_download(&self, api: &'_ Api, enabled: bool) -> Result<(), Error> {
+        let endpoint = "/api/v2/torrents/toggleSequentialDownload";
+        toggle_to(api, &self.hash, endpoint, enabled, sequential_download).await
+    }
+
+    async fn set_first_last_piece_priority(
+        &self,
+        api: &'_ Api,
+        enabled: bool,
+    ) -> Result<(), Error> {
+        let endpoint = "/api/v2/torrents/toggleFirstLastPiecePrio";
+        toggle_to(
+            api,
+            &self.hash,
+            endpoint,
+            enabled,
+            first_last_piece_priority,
+        )
+        .await
+    }
+
+    async fn set_force_start(&self, api: &'_ Api, enabled: bool) -> Result<(), Error> {
+        set_flags(api, &self.hash, "/api/v2/torrents/setForceStart", enabled).await
+    }
+
+    async fn set_super_seeding(&self, api: &'_ Api, enabled: bool) -> Result<(), Error> {
+        set_flags(api, &self.hash, "/api/v2/torrents/setSuperSeeding", enabled).await
+    }
+
+    async fn set_auto_management(&self, api: &'_ Api, enabled: bool) -> Result<(), Error> {
+        set_auto_management(api, &self.hash, enabled).await
+    }
+}
+
+#[async_trait]
+impl Flags<Api> for Vec<Hash> {
+    async fn set_sequential_download(&self, api: &'_ Api, enabled: bool) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        let endpoint = "/api/v2/torrents/toggleSequentialDownload";
+        toggle_to(api, &hash_url, endpoint, enabled, sequential_download).await
+    }
+
+    async fn set_first_last_piece_priority(
+        &self,
+        api: &'_ Api,
+        enabled: bool,
+    ) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        let endpoint = "/api/v2/torrents/toggleFirstLastPiecePrio";
+        toggle_to(api, &hash_url, endpoint, enabled, first_last_piece_priority).await
+    }
+
+    async fn set_force_start(&self, api: &'_ Api, enabled: bool) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        set_flags(api, &hash_url, "/api/v2/torrents/setForceStart", enabled).await
+    }
+
+    async fn set_super_seeding(&self, api: &'_ Api, enabled: bool) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        set_flags(api, &hash_url, "/api/v2/torrents/setSuperSeeding", enabled).await
+    }
+
+    async fn set_auto_management(&self, api: &'_ Api, enabled: bool) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let hash_url = QueryConcat::query_concat(&self.as_slice(), '|');
+        set_auto_management(api, &hash_url, enabled).await
+    }
+}
+
+#[derive(Serialize)]
+struct AddTrackersForm<'a> {
+    hash: &'a str,
+    urls: String,
+}
+
+#[derive(Serialize)]
+struct EditTrackerForm<'a> {
+    hash: &'a str,
+    #[serde(rename = "origUrl")]
+    orig_url: &'a str,
+    #[serde(rename = "newUrl")]
+    new_url: &'a str,
 }
 
-impl<'hash, 'tag> TagsUrlHelper<'hash, 'tag> {
-    fn url(self) -> Result<String, Error> {
-        let mut url = String::with_capacity(25);
-        let hashes = QueryConcat::query_concat(self.hashes, '|');
-        let tags = QueryConcat::query_concat(&self.tags, ',');
+#[derive(Serialize)]
+struct RemoveTrackersForm<'a> {
+    hash: &'a str,
+    urls: String,
+}
 
-        push_own!(prealloc; url, "hashes=", &hashes,"&tags=", &tags);
+#[async_trait]
+impl Trackers<Api> for Torrent {
+    async fn add_trackers(&self, api: &'_ Api, urls: &[String]) -> Result<(), Error> {
+        self.hash.add_trackers(api, urls).await
+    }
 
-        Ok(url)
+    async fn edit_tracker(&self, api: &'_ Api, orig_url: &str, new_url: &str) -> Result<(), Error> {
+        self.hash.edit_tracker(api, orig_url, new_url).await
+    }
+
+    async fn remove_trackers(&self, api: &'_ Api, urls: &[String]) -> Result<(), Error> {
+        self.hash.remove_trackers(api, urls).await
+    }
+}
+
+#[async_trait]
+impl Trackers<Api> for Hash {
+    async fn add_trackers(&self, api: &'_ Api, urls: &[String]) -> Result<(), Error> {
+        let addr = push_own! {api.address, "/api/v2/torrents/addTrackers"};
+        let form = AddTrackersForm {
+            hash: &self.hash,
+            urls: urls.join("\n"),
+        };
+
+        let res = api.send(|client| client.post(&addr).form(&form)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+
+    async fn edit_tracker(&self, api: &'_ Api, orig_url: &str, new_url: &str) -> Result<(), Error> {
+        let addr = push_own! {api.address, "/api/v2/torrents/editTracker"};
+        let form = EditTrackerForm {
+            hash: &self.hash,
+            orig_url,
+            new_url,
+        };
+
+        let res = api.send(|client| client.post(&addr).form(&form)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+
+    async fn remove_trackers(&self, api: &'_ Api, urls: &[String]) -> Result<(), Error> {
+        let addr = push_own! {api.address, "/api/v2/torrents/removeTrackers"};
+        let form = RemoveTrackersForm {
+            hash: &self.hash,
+            urls: urls.join("|"),
+        };
+
+        let res = api.send(|client| client.post(&addr).form(&form)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+}
+
+/// qbittorrent only accepts a single hash for the tracker endpoints, so each torrent is
+/// updated in turn. Stops at the first torrent that fails.
+#[async_trait]
+impl Trackers<Api> for Vec<Hash> {
+    async fn add_trackers(&self, api: &'_ Api, urls: &[String]) -> Result<(), Error> {
+        for hash in self {
+            hash.add_trackers(api, urls).await?;
+        }
+        Ok(())
+    }
+
+    async fn edit_tracker(&self, api: &'_ Api, orig_url: &str, new_url: &str) -> Result<(), Error> {
+        for hash in self {
+            hash.edit_tracker(api, orig_url, new_url).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_trackers(&self, api: &'_ Api, urls: &[String]) -> Result<(), Error> {
+        for hash in self {
+            hash.remove_trackers(api, urls).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WebSeeds<Api> for Torrent {
+    async fn add_web_seeds(&self, api: &'_ Api, urls: &[String]) -> Result<(), Error> {
+        self.hash.add_web_seeds(api, urls).await
+    }
+
+    async fn edit_web_seed(
+        &self,
+        api: &'_ Api,
+        orig_url: &str,
+        new_url: &str,
+    ) -> Result<(), Error> {
+        self.hash.edit_web_seed(api, orig_url, new_url).await
+    }
+
+    async fn remove_web_seeds(&self, api: &'_ Api, urls: &[String]) -> Result<(), Error> {
+        self.hash.remove_web_seeds(api, urls).await
+    }
+}
+
+/// The web seed endpoints take the same forms as the tracker ones
+#[async_trait]
+impl WebSeeds<Api> for Hash {
+    async fn add_web_seeds(&self, api: &'_ Api, urls: &[String]) -> Result<(), Error> {
+        let addr = push_own! {api.address, "/api/v2/torrents/addWebSeeds"};
+        let form = AddTrackersForm {
+            hash: &self.hash,
+            urls: urls.join("|"),
+        };
+
+        let res = api.send(|client| client.post(&addr).form(&form)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+
+    async fn edit_web_seed(
+        &self,
+        api: &'_ Api,
+        orig_url: &str,
+        new_url: &str,
+    ) -> Result<(), Error> {
+        let addr = push_own! {api.address, "/api/v2/torrents/editWebSeed"};
+        let form = EditTrackerForm {
+            hash: &self.hash,
+            orig_url,
+            new_url,
+        };
+
+        let res = api.send(|client| client.post(&addr).form(&form)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+
+    async fn remove_web_seeds(&self, api: &'_ Api, urls: &[String]) -> Result<(), Error> {
+        let addr = push_own! {api.address, "/api/v2/torrents/removeWebSeeds"};
+        let form = RemoveTrackersForm {
+            hash: &self.hash,
+            urls: urls.join("|"),
+        };
+
+        let res = api.send(|client| client.post(&addr).form(&form)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct FilePriorityForm<'a> {
+    hash: &'a str,
+    id: String,
+    priority: FilePriority,
+}
+
+#[derive(Serialize)]
+struct RenamePathForm<'a> {
+    hash: &'a str,
+    #[serde(rename = "oldPath")]
+    old_path: &'a str,
+    #[serde(rename = "newPath")]
+    new_path: &'a str,
+}
+
+impl<'a> RenamePathForm<'a> {
+    async fn send(self, api: &'_ Api, endpoint: &str) -> Result<(), Error> {
+        let addr = push_own! {api.address, endpoint};
+
+        let res = api.send(|client| client.post(&addr).form(&self)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Files<Api> for Torrent {
+    async fn set_file_priority(
+        &self,
+        api: &'_ Api,
+        files: &[usize],
+        priority: FilePriority,
+    ) -> Result<(), Error> {
+        self.hash.set_file_priority(api, files, priority).await
+    }
+
+    async fn rename_file(&self, api: &'_ Api, old_path: &str, new_path: &str) -> Result<(), Error> {
+        self.hash.rename_file(api, old_path, new_path).await
+    }
+
+    async fn rename_folder(
+        &self,
+        api: &'_ Api,
+        old_path: &str,
+        new_path: &str,
+    ) -> Result<(), Error> {
+        self.hash.rename_folder(api, old_path, new_path).await
+    }
+}
+
+#[async_trait]
+impl Files<Api> for Hash {
+    async fn set_file_priority(
+        &self,
+        api: &'_ Api,
+        files: &[usize],
+        priority: FilePriority,
+    ) -> Result<(), Error> {
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        let addr = push_own! {api.address, "/api/v2/torrents/filePrio"};
+        let ids: Vec<String> = files.iter().map(|x| x.to_string()).collect();
+        let form = FilePriorityForm {
+            hash: &self.hash,
+            id: ids.join("|"),
+            priority,
+        };
+
+        let res = api.send(|client| client.post(&addr).form(&form)).await?;
+        check_status(res).await?;
+        Ok(())
+    }
+
+    async fn rename_file(&self, api: &'_ Api, old_path: &str, new_path: &str) -> Result<(), Error> {
+        let form = RenamePathForm {
+            hash: &self.hash,
+            old_path,
+            new_path,
+        };
+        form.send(api, "/api/v2/torrents/renameFile").await
+    }
+
+    async fn rename_folder(
+        &self,
+        api: &'_ Api,
+        old_path: &str,
+        new_path: &str,
+    ) -> Result<(), Error> {
+        let form = RenamePathForm {
+            hash: &self.hash,
+            old_path,
+            new_path,
+        };
+        form.send(api, "/api/v2/torrents/renameFolder").await
+    }
+}
+
+impl<'a> TorrentInfo<'a> {
+    pub async fn set_priority(&self, api: &Api, priority: FilePriority) -> Result<(), Error> {
+        self.hash()
+            .set_file_priority(api, &[*self.index()], priority)
+            .await
+    }
+
+    /// Move this file to `new_name`, relative to the torrent's root
+    pub async fn rename(&self, api: &Api, new_name: &str) -> Result<(), Error> {
+        self.hash().rename_file(api, self.name(), new_name).await
+    }
+}
+
+#[derive(Serialize)]
+struct TagsForm {
+    hashes: String,
+    tags: String,
+}
+
+impl TagsForm {
+    async fn send(self, api: &'_ Api, endpoint: &str) -> Result<(), Error> {
+        let addr = push_own! {api.address, endpoint};
+
+        let res = api.send(|client| client.post(&addr).form(&self)).await?;
+        check_status(res).await?;
+        Ok(())
     }
 }
 
 #[async_trait]
 impl Tags<Api, [String]> for Torrent {
     async fn add_tag(&self, api: &'_ Api, tags: &'_ [String]) -> Result<(), Error> {
-        self.hash.add_tag(&api, tags).await
+        self.hash.add_tag(api, tags).await
+    }
+
+    async fn remove_tag(&self, api: &'_ Api, tags: &'_ [String]) -> Result<(), Error> {
+        self.hash.remove_tag(api, tags).await
     }
 }
 
 #[async_trait]
 impl Tags<Api, [String]> for Hash {
     async fn add_tag(&self, api: &'_ Api, tags: &'_ [String]) -> Result<(), Error> {
-        let helper = TagsUrlHelper {
-            hashes: &[self],
-            tags,
-        };
-        let addr = push_own! {api.address, "/api/v2/torrents/addTags?", &helper.url()?};
+        vec![self.clone()].add_tag(api, tags).await
+    }
 
-        let res = api
-            .client
-            .post(&addr)
-            .headers(api.make_headers()?)
-            .send()
-            .await;
+    async fn remove_tag(&self, api: &'_ Api, tags: &'_ [String]) -> Result<(), Error> {
+        vec![self.clone()].remove_tag(api, tags).await
+    }
+}
 
-        match res?.error_for_status() {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Error::from(e)),
+#[async_trait]
+impl Tags<Api, [String]> for Vec<Hash> {
+    async fn add_tag(&self, api: &'_ Api, tags: &'_ [String]) -> Result<(), Error> {
+        if self.is_empty() || tags.is_empty() {
+            return Ok(());
         }
+
+        let form = TagsForm {
+            hashes: QueryConcat::query_concat(&self.as_slice(), '|'),
+            tags: tags.join(","),
+        };
+        form.send(api, "/api/v2/torrents/addTags").await
+    }
+
+    async fn remove_tag(&self, api: &'_ Api, tags: &'_ [String]) -> Result<(), Error> {
+        if self.is_empty() || tags.is_empty() {
+            return Ok(());
+        }
+
+        let form = TagsForm {
+            hashes: QueryConcat::query_concat(&self.as_slice(), '|'),
+            tags: tags.join(","),
+        };
+        form.send(api, "/api/v2/torrents/removeTags").await
     }
 }
 
@@ -316,11 +1451,7 @@ impl Recheck<Api> for Hash {
     async fn recheck(&self, api: &'_ Api) -> Result<(), Error> {
         let addr = push_own!(api.address, "/api/v2/torrents/recheck?hashes=", &self.hash);
 
-        api.client
-            .get(&addr)
-            .headers(api.make_headers()?)
-            .send()
-            .await?;
+        api.send(|client| client.get(&addr)).await?;
 
         Ok(())
     }
@@ -333,3 +1464,23 @@ impl Recheck<Api> for Torrent {
         Ok(())
     }
 }
+
+#[async_trait]
+impl Peers<Api> for Hash {
+    async fn sync_peers(&self, api: &'_ Api, state: &mut PeersSync) -> Result<(), Error> {
+        let rid = state.rid_for(self).to_string();
+        let addr = push_own! {api.address, "/api/v2/sync/torrentPeers?hash=", self, "&rid=", &rid};
+
+        let res = api.send(|client| client.get(&addr)).await?.bytes().await?;
+
+        let peers = serde_json::from_slice(&res)?;
+        state.apply(self, peers)
+    }
+}
+
+#[async_trait]
+impl Peers<Api> for Torrent {
+    async fn sync_peers(&self, api: &'_ Api, state: &mut PeersSync) -> Result<(), Error> {
+        self.hash.sync_peers(api, state).await
+    }
+}
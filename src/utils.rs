@@ -1,4 +1,17 @@
+use reqwest::StatusCode;
+
 use super::data::Hash;
+use super::error::Error;
+
+/// Turn the status codes qbittorrent uses to report invalid arguments into typed errors
+pub(crate) async fn check_status(res: reqwest::Response) -> Result<reqwest::Response, Error> {
+    match res.status() {
+        StatusCode::BAD_REQUEST => Err(Error::BadRequest(res.text().await?)),
+        StatusCode::NOT_FOUND => Err(Error::NotFound(res.text().await?)),
+        StatusCode::CONFLICT => Err(Error::Conflict(res.text().await?)),
+        _ => Ok(res.error_for_status()?),
+    }
+}
 
 pub trait QueryConcat {
     fn query_concat(&self, sep: char) -> String;
@@ -6,13 +19,13 @@ pub trait QueryConcat {
 
 impl QueryConcat for Hash {
     fn query_concat(&self, _: char) -> String {
-        return self.hash.to_string();
+        self.hash.to_string()
     }
 }
 
 impl QueryConcat for &str {
     fn query_concat(&self, _: char) -> String {
-        return self.to_string();
+        self.to_string()
     }
 }
 